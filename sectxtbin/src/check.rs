@@ -0,0 +1,195 @@
+use super::files::read_input_bytes;
+use super::settings::CheckCommand;
+use super::verify::{load_keyring, verify_signature};
+use anyhow::Result;
+use sectxtlib::{Keyring, SecurityTxt, SecurityTxtOptions, Severity, Span};
+use std::path::{Path, PathBuf};
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "info",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+// Messages follow the common "file:line:column: severity: message" format understood by editors and CI systems. The
+// code of each problem is appended to the severity, and can be looked up with `sectxt explain`.
+fn report(name: &str, severity: Severity, code: &str, span: Option<&Span>, message: &str) {
+    match span {
+        Some(span) => println!(
+            "{name}:{}:{}: {}[{code}]: {message}",
+            span.line,
+            span.column,
+            severity_name(severity)
+        ),
+        None => println!("{name}: {}[{code}]: {message}", severity_name(severity)),
+    }
+}
+
+/// Checks a single file, and returns whether it is valid
+///
+/// If a keyring is given, the signature of a signed file must be valid as well.
+fn check_file(path: &Path, options: &SecurityTxtOptions, keyring: Option<&Keyring>) -> Result<bool> {
+    let name = match path == Path::new("-") {
+        true => "<stdin>".to_owned(),
+        false => path.display().to_string(),
+    };
+    let bytes = read_input_bytes(path, options)?;
+    // Input which is too large or not properly encoded cannot be diagnosed at all.
+    let text = match SecurityTxt::decode_bytes(&bytes, options) {
+        Ok(text) => text,
+        Err(err) => {
+            report(&name, Severity::Error, err.code(), err.span(), &err.to_string());
+            return Ok(false);
+        }
+    };
+
+    let diagnostics = SecurityTxt::diagnose(&text, options);
+    for finding in &diagnostics.findings {
+        report(
+            &name,
+            finding.severity,
+            finding.code(),
+            finding.span(),
+            &finding.error.to_string(),
+        );
+    }
+
+    // Every error has been reported as a finding.
+    let Ok(txt) = diagnostics.into_result() else {
+        return Ok(false);
+    };
+
+    let mut lints = txt.lint();
+    lints.sort_by_key(|l| l.span.as_ref().map_or((usize::MAX, 0), |s| (s.line, s.column)));
+    for lint in lints {
+        report(
+            &name,
+            lint.severity,
+            lint.code(),
+            lint.span.as_ref(),
+            &lint.kind.to_string(),
+        );
+    }
+
+    let mut findings = vec![];
+    if let Some(keyring) = keyring {
+        verify_signature(keyring, &text, options, &name, &mut findings);
+    }
+    for finding in &findings {
+        let severity = match finding.severity {
+            "error" => Severity::Error,
+            _ => Severity::Warning,
+        };
+        report(&name, severity, finding.code, None, &finding.message);
+    }
+
+    Ok(findings.iter().all(|f| f.severity != "error"))
+}
+
+pub fn check(command: &CheckCommand, options: &SecurityTxtOptions) -> Result<()> {
+    let stdin = [PathBuf::from("-")];
+    let inputs = match command.inputs.is_empty() {
+        true => &stdin[..],
+        false => &command.inputs[..],
+    };
+
+    let keyring = match command.key.is_empty() {
+        true => None,
+        false => Some(load_keyring(&command.key)?),
+    };
+
+    let mut invalid = 0;
+    for path in inputs {
+        if !check_file(path, options, keyring.as_ref())? {
+            invalid += 1;
+        }
+    }
+
+    match invalid {
+        0 => Ok(()),
+        1 if inputs.len() == 1 => anyhow::bail!("file is not a valid security.txt file"),
+        _ => anyhow::bail!("{invalid} of {} files are not valid security.txt files", inputs.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const VALID: &str = "Contact: mailto:security@example.com\nExpires: 2999-01-01T00:00:00Z\n";
+
+    fn write_temp(name: &str, text: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("sectxt-check-{}-{name}", std::process::id()));
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    fn run(inputs: Vec<PathBuf>, options: &SecurityTxtOptions) -> Result<()> {
+        let command = CheckCommand { key: vec![], inputs };
+        check(&command, options)
+    }
+
+    #[test]
+    fn test_check_warnings_succeed() {
+        // The "Expires" field is more than a year in the future, and the lenient grammar accepts the tab.
+        let path = write_temp("warnings", &VALID.replace("Contact: ", "Contact:\t"));
+        let result = run(vec![path.clone()], &SecurityTxtOptions::new(false));
+        fs::remove_file(path).unwrap();
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_check_errors_fail() {
+        let invalid = write_temp("invalid", "Contact: mailto:security@example.com\n");
+        let valid = write_temp("valid", VALID);
+        let tab = write_temp("tab", &VALID.replace("Contact: ", "Contact:\t"));
+
+        let strict = SecurityTxtOptions::new(true);
+        let single = run(vec![invalid.clone()], &strict);
+        let multiple = run(vec![valid.clone(), invalid.clone()], &strict);
+        // A deviation which is a warning under the lenient grammar is an error under the strict one.
+        let tab_strict = run(vec![tab.clone()], &strict);
+        for path in [invalid, valid, tab] {
+            fs::remove_file(path).unwrap();
+        }
+
+        assert_eq!(single.unwrap_err().to_string(), "file is not a valid security.txt file");
+        assert_eq!(
+            multiple.unwrap_err().to_string(),
+            "1 of 2 files are not valid security.txt files"
+        );
+        assert!(tab_strict.is_err());
+    }
+
+    #[test]
+    fn test_check_invalid_input() {
+        let path = std::env::temp_dir().join(format!("sectxt-check-{}-encoding", std::process::id()));
+        let mut bytes = VALID.as_bytes().to_vec();
+        bytes.insert(9, 0xFF);
+        fs::write(&path, bytes).unwrap();
+
+        let options = SecurityTxtOptions::new(false);
+        let encoding = run(vec![path.clone()], &options);
+        let size = run(
+            vec![path.clone()],
+            &SecurityTxtOptions {
+                max_size: Some(16),
+                ..options
+            },
+        );
+        fs::remove_file(path).unwrap();
+
+        assert!(encoding.is_err());
+        assert!(size.is_err());
+    }
+
+    #[test]
+    fn test_check_missing_file() {
+        let path = std::env::temp_dir().join("sectxt-check-does-not-exist");
+        assert!(run(vec![path], &SecurityTxtOptions::new(true)).is_err());
+    }
+}
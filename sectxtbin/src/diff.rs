@@ -0,0 +1,98 @@
+use super::files::read_input_bytes;
+use super::settings::DiffCommand;
+use anyhow::{Context, Result};
+use sectxtlib::{SecurityTxt, SecurityTxtOptions};
+use similar::{ChangeTag, TextDiff};
+use std::path::Path;
+
+/// Reads and parses a file, which is decoded in the same way as by `check`
+fn read_securitytxt(path: &Path, options: &SecurityTxtOptions) -> Result<SecurityTxt> {
+    let bytes = read_input_bytes(path, options)?;
+    SecurityTxt::decode_bytes(&bytes, options)
+        .and_then(|text| SecurityTxt::parse_with(&text, options))
+        .with_context(|| format!("{} is not a valid security.txt file", path.display()))
+}
+
+/// Returns the lines which were removed or added, prefixed by "- " or "+ ", in the order they appear in the files
+fn changed_lines(old: &str, new: &str) -> Vec<String> {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .filter_map(|change| {
+            let line = change.value().trim_end_matches(['\r', '\n']);
+            match change.tag() {
+                ChangeTag::Delete => Some(format!("- {line}")),
+                ChangeTag::Insert => Some(format!("+ {line}")),
+                ChangeTag::Equal => None,
+            }
+        })
+        .collect()
+}
+
+pub fn diff(command: &DiffCommand, options: &SecurityTxtOptions) -> Result<()> {
+    let old = read_securitytxt(&command.old, options)?;
+    let new = read_securitytxt(&command.new, options)?;
+
+    // Both files are compared in their rendered form, so that comments and formatting are ignored.
+    for line in changed_lines(&old.to_string(), &new.to_string()) {
+        println!("{line}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_lines() {
+        let old = "Contact: mailto:a@example.com\nContact: mailto:b@example.com\nExpires: 2030-01-01T00:00:00Z\n";
+        let new = "Contact: mailto:b@example.com\nContact: mailto:a@example.com\nExpires: 2031-01-01T00:00:00Z\n";
+
+        assert_eq!(
+            changed_lines(old, new),
+            [
+                "+ Contact: mailto:b@example.com",
+                "- Contact: mailto:b@example.com",
+                "- Expires: 2030-01-01T00:00:00Z",
+                "+ Expires: 2031-01-01T00:00:00Z",
+            ]
+        );
+        assert!(changed_lines(old, old).is_empty());
+    }
+
+    #[test]
+    fn test_read_securitytxt() {
+        let text = "Contact: mailto:security@example.com\nExpires: 2999-01-01T00:00:00Z\n";
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        let path = std::env::temp_dir().join(format!("sectxt-diff-{}-utf16", std::process::id()));
+        std::fs::write(&path, utf16).unwrap();
+
+        let options = SecurityTxtOptions::new(false);
+        let decoded = read_securitytxt(&path, &options);
+        let strict = read_securitytxt(&path, &SecurityTxtOptions::new(true));
+        let size = read_securitytxt(
+            &path,
+            &SecurityTxtOptions {
+                max_size: Some(16),
+                ..options.clone()
+            },
+        );
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(decoded.unwrap(), SecurityTxt::parse_with(text, &options).unwrap());
+        assert!(strict.is_err());
+        assert!(size.is_err());
+    }
+
+    #[test]
+    fn test_changed_lines_duplicates() {
+        let old = "Contact: mailto:a@example.com\n";
+        let new = "Contact: mailto:a@example.com\nContact: mailto:a@example.com\n";
+
+        assert_eq!(changed_lines(old, new), ["+ Contact: mailto:a@example.com"]);
+    }
+}
@@ -0,0 +1,29 @@
+use super::findings::SCAN_EXPLANATIONS;
+use super::settings::ExplainCommand;
+use anyhow::Result;
+use sectxtlib::Explanation;
+
+/// Returns the explanations of all codes, including those of scan findings
+fn all() -> impl Iterator<Item = &'static Explanation> {
+    Explanation::all().iter().chain(SCAN_EXPLANATIONS)
+}
+
+pub fn explain(command: &ExplainCommand) -> Result<()> {
+    let Some(code) = &command.code else {
+        for explanation in all() {
+            println!("{}: {}", explanation.code, explanation.summary);
+        }
+        return Ok(());
+    };
+
+    let explanation = all()
+        .find(|e| e.code.eq_ignore_ascii_case(code.trim()))
+        .ok_or_else(|| anyhow::anyhow!("unknown code {code:?}, run `sectxt explain` to list all codes"))?;
+
+    println!("{}: {}\n", explanation.code, explanation.summary);
+    println!("{}\n", explanation.explanation);
+    println!("Fix: {}\n", explanation.fix);
+    println!("See {} ({})", explanation.reference, explanation.url);
+
+    Ok(())
+}
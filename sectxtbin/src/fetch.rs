@@ -0,0 +1,39 @@
+use super::files::write_output;
+use super::findings::ScanFinding;
+use super::network::client;
+use super::settings::FetchCommand;
+use super::website::{normalize_input, Website};
+use anyhow::{Context, Result};
+use sectxtlib::{SecurityTxt, SecurityTxtOptions};
+
+#[tokio::main]
+async fn download(
+    command: &FetchCommand,
+    options: &SecurityTxtOptions,
+    findings: &mut Vec<ScanFinding>,
+) -> Result<(String, String)> {
+    let client = client(command.timeout);
+
+    let website = Website::try_from(&normalize_input(&command.domain)[..])?;
+    website.fetch(&client, options, command.max_body_size, findings).await
+}
+
+pub fn fetch(command: &FetchCommand, options: &SecurityTxtOptions) -> Result<()> {
+    let mut findings = vec![];
+    let result = download(command, options, &mut findings);
+
+    for finding in &findings {
+        eprintln!(
+            "{}: {}: {} ({})",
+            finding.severity, finding.code, finding.message, finding.url
+        );
+    }
+    let (url, text) = result?;
+
+    write_output(command.output.as_deref(), &text)?;
+
+    // The file is written even if it is invalid, so that it can be inspected.
+    SecurityTxt::parse_with(&text, options).with_context(|| format!("{url} is not a valid security.txt file"))?;
+
+    Ok(())
+}
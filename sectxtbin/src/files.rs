@@ -0,0 +1,40 @@
+use anyhow::{Context, Result};
+use sectxtlib::{SecurityTxt, SecurityTxtOptions};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Reads a file to a string, where a path of "-" denotes standard input
+pub fn read_input(path: &Path) -> Result<String> {
+    if path == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .context("unable to read standard input")?;
+        return Ok(text);
+    }
+
+    fs::read_to_string(path).with_context(|| format!("unable to read {}", path.display()))
+}
+
+/// Reads a file as raw bytes, where a path of "-" denotes standard input
+///
+/// Reading stops once the input exceeds the `max_size` option.
+pub fn read_input_bytes(path: &Path, options: &SecurityTxtOptions) -> Result<Vec<u8>> {
+    if path == Path::new("-") {
+        return SecurityTxt::read_bytes(std::io::stdin(), options).context("unable to read standard input");
+    }
+
+    let file = fs::File::open(path).with_context(|| format!("unable to read {}", path.display()))?;
+    SecurityTxt::read_bytes(file, options).with_context(|| format!("unable to read {}", path.display()))
+}
+
+/// Writes a string to a file, or to standard output if no path is given
+pub fn write_output(path: Option<&Path>, text: &str) -> Result<()> {
+    match path {
+        Some(path) => fs::write(path, text).with_context(|| format!("unable to write {}", path.display())),
+        None => std::io::stdout()
+            .write_all(text.as_bytes())
+            .context("unable to write to standard output"),
+    }
+}
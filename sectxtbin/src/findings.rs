@@ -0,0 +1,234 @@
+use super::status::Attempt;
+use sectxtlib::Explanation;
+use serde::Serialize;
+use url::Url;
+
+/// A problem found while scanning a domain, which concerns the way the file is served rather than its content
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ScanFinding {
+    /// The stable code identifying this kind of finding, e.g., "STXT201"
+    pub code: &'static str,
+
+    /// Either "warning" or "error"
+    pub severity: &'static str,
+
+    /// A description of the problem
+    pub message: String,
+
+    /// The URL the problem relates to
+    pub url: String,
+}
+
+impl ScanFinding {
+    pub fn warning(code: &'static str, message: String, url: &str) -> Self {
+        ScanFinding {
+            code,
+            severity: "warning",
+            message,
+            url: url.to_owned(),
+        }
+    }
+
+    pub fn error(code: &'static str, message: String, url: &str) -> Self {
+        ScanFinding {
+            code,
+            severity: "error",
+            message,
+            url: url.to_owned(),
+        }
+    }
+}
+
+/// Returns the findings about the redirect chain of a single attempt
+///
+/// RFC 9116 requires the file to be served over HTTPS, so a redirect to HTTP is an error. Redirects to another origin
+/// are allowed, but should be scrutinized, as the file may not be under the control of the domain's owner.
+pub fn redirect_findings(attempt: &Attempt) -> Vec<ScanFinding> {
+    let mut findings = vec![];
+    let chain = std::iter::once(&attempt.url).chain(&attempt.redirects);
+
+    for (from, to) in chain.clone().zip(chain.skip(1)) {
+        let (Ok(from_url), Ok(to_url)) = (Url::parse(from), Url::parse(to)) else {
+            continue;
+        };
+
+        if from_url.scheme() == "https" && to_url.scheme() == "http" {
+            findings.push(ScanFinding::error(
+                "STXT201",
+                format!("redirect from {from} downgrades to insecure HTTP"),
+                to,
+            ));
+        } else if from_url.origin() != to_url.origin() {
+            findings.push(ScanFinding::warning(
+                "STXT202",
+                format!("redirect from {from} leads to a different origin"),
+                to,
+            ));
+        }
+    }
+
+    findings
+}
+
+/// Explanations of the codes of scan findings, which complement those of the library
+pub const SCAN_EXPLANATIONS: &[Explanation] = &[
+    Explanation {
+        code: "STXT201",
+        summary: "redirect downgrades to insecure HTTP",
+        explanation: "Requesting the file leads to a redirect to an HTTP URL. The file must be served over HTTPS, so \
+            that attackers cannot tamper with its content in transit.",
+        reference: "RFC 9116, Section 3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-3",
+        fix: "Serve the file over HTTPS, and only redirect to HTTPS URLs.",
+    },
+    Explanation {
+        code: "STXT202",
+        summary: "redirect leads to a different origin",
+        explanation:
+            "Requesting the file leads to a redirect to another scheme, host, or port. Redirects are allowed, \
+            but the file may then not be under the control of the owner of the domain.",
+        reference: "RFC 9116, Section 3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-3",
+        fix: "Serve the file from the domain itself, or make sure the target of the redirect is trustworthy.",
+    },
+    Explanation {
+        code: "STXT203",
+        summary: "HTTP content type invalid or not specified",
+        explanation: "The response has no Content-Type header, or its value is not a valid media type. The file \
+            should be served as \"text/plain\".",
+        reference: "RFC 9116, Section 3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-3",
+        fix: "Serve the file with the header \"Content-Type: text/plain; charset=utf-8\".",
+    },
+    Explanation {
+        code: "STXT204",
+        summary: "HTTP content type is not text/plain",
+        explanation: "The response declares a media type other than \"text/plain\", which clients may not treat as a \
+            plain text file.",
+        reference: "RFC 9116, Section 3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-3",
+        fix: "Serve the file with the header \"Content-Type: text/plain; charset=utf-8\".",
+    },
+    Explanation {
+        code: "STXT205",
+        summary: "HTTP charset not specified",
+        explanation: "The Content-Type header of the response has no charset parameter, so clients have to guess the \
+            encoding of the file. It is decoded as UTF-8.",
+        reference: "RFC 9116, Section 3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-3",
+        fix: "Add the parameter \"charset=utf-8\" to the Content-Type header.",
+    },
+    Explanation {
+        code: "STXT206",
+        summary: "HTTP charset is not utf-8",
+        explanation:
+            "The Content-Type header of the response declares a charset other than UTF-8. The file is decoded \
+            with the declared charset if it is known, and as UTF-8 otherwise.",
+        reference: "RFC 9116, Section 3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-3",
+        fix: "Save the file as UTF-8, and add the parameter \"charset=utf-8\" to the Content-Type header.",
+    },
+    Explanation {
+        code: "STXT207",
+        summary: "HTTP body is an HTML document",
+        explanation: "The location serves an HTML document instead of a security.txt file. This often happens when a \
+            web application answers every path with the same page.",
+        reference: "RFC 9116, Section 3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-3",
+        fix: "Serve the security.txt file at this location, or respond with status 404 if there is none.",
+    },
+    Explanation {
+        code: "STXT208",
+        summary: "signature does not match the file",
+        explanation: "The file is signed by a known key, but the signature does not match the signed text. The file \
+            may have been modified after it was signed.",
+        reference: "RFC 9116, Section 2.3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.3",
+        fix: "Sign the file again after making any changes to it.",
+    },
+    Explanation {
+        code: "STXT209",
+        summary: "signature was made by an unknown key",
+        explanation: "The file is signed, but not by any of the keys given with --key or linked in its \
+            \"Encryption\" fields, so its authenticity cannot be established.",
+        reference: "RFC 9116, Section 2.3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.3",
+        fix: "Sign the file with a key which is linked in an \"Encryption\" field, or pass the key with --key.",
+    },
+    Explanation {
+        code: "STXT210",
+        summary: "signature cannot be verified",
+        explanation: "The file is signed, but the signature is malformed, is not a cleartext signature, uses a hash \
+            algorithm which is insecure or not declared in the \"Hash\" armor header, or uses an unsupported \
+            algorithm.",
+        reference: "RFC 9116, Section 2.3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.3",
+        fix: "Sign the file again with a current OpenPGP implementation, using a hash algorithm such as SHA-256.",
+    },
+    Explanation {
+        code: "STXT211",
+        summary: "key linked in an Encryption field cannot be loaded",
+        explanation: "An \"Encryption\" field links to an HTTPS URL, but no OpenPGP public key could be loaded from \
+            it, so it cannot be used to verify the signature of the file.",
+        reference: "RFC 9116, Section 2.5.4",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.5.4",
+        fix: "Serve the public key at the linked URL, either ASCII-armored or in binary form.",
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(url: &str, redirects: &[&str]) -> Attempt {
+        Attempt {
+            redirects: redirects.iter().map(|r| r.to_string()).collect(),
+            ..Attempt::new(url)
+        }
+    }
+
+    #[test]
+    fn test_redirect_downgrade() {
+        let attempt = attempt(
+            "https://example.com/.well-known/security.txt",
+            &["http://example.com/.well-known/security.txt"],
+        );
+
+        assert_eq!(
+            redirect_findings(&attempt),
+            [ScanFinding::error(
+                "STXT201",
+                "redirect from https://example.com/.well-known/security.txt downgrades to insecure HTTP".to_owned(),
+                "http://example.com/.well-known/security.txt",
+            )]
+        );
+    }
+
+    #[test]
+    fn test_redirect_cross_origin() {
+        let attempt = attempt(
+            "https://example.com/.well-known/security.txt",
+            &[
+                "https://example.com/security.txt",
+                "https://www.example.com/security.txt",
+                "https://www.example.com:8443/security.txt",
+            ],
+        );
+
+        let findings = redirect_findings(&attempt);
+        assert_eq!(
+            findings.iter().map(|f| f.code).collect::<Vec<_>>(),
+            ["STXT202", "STXT202"]
+        );
+        assert_eq!(findings[0].severity, "warning");
+        assert_eq!(findings[0].url, "https://www.example.com/security.txt");
+        assert_eq!(findings[1].url, "https://www.example.com:8443/security.txt");
+    }
+
+    #[test]
+    fn test_redirect_same_origin() {
+        let url = "https://example.com/.well-known/security.txt";
+        assert!(redirect_findings(&attempt(url, &[])).is_empty());
+        assert!(redirect_findings(&attempt(url, &["https://example.com/security.txt"])).is_empty());
+    }
+}
@@ -0,0 +1,206 @@
+use super::files::write_output;
+use super::settings::GenerateCommand;
+use super::sign::load_key;
+use anyhow::{Context, Result};
+use chrono::{Duration, SubsecRound, Utc};
+use sectxtlib::{RenderOptions, SecurityTxtBuilder, SecurityTxtOptions};
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Parses a duration consisting of a number and a unit, which is one of "h", "d", or "w"
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| format!("invalid duration {value:?}, expected a number followed by a unit"))?;
+
+    match unit {
+        "h" => Ok(Duration::hours(amount)),
+        "d" => Ok(Duration::days(amount)),
+        "w" => Ok(Duration::weeks(amount)),
+        _ => Err(format!("invalid unit in duration {value:?}, expected one of h, d, w")),
+    }
+}
+
+/// Parses an extension field given as "name=value"
+pub fn parse_extension(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => Ok((name.trim().to_owned(), value.trim().to_owned())),
+        _ => Err(format!("invalid extension field {value:?}, expected name=value")),
+    }
+}
+
+/// The values of fields which were not given as flags, but entered interactively
+#[derive(Debug, Default, PartialEq)]
+struct Answers {
+    contact: Vec<String>,
+    canonical: Vec<String>,
+    policy: Vec<String>,
+    encryption: Vec<String>,
+    lang: Option<String>,
+}
+
+/// Asks a question on `output`, and returns the values of the answer read from `input`, which are separated by spaces
+fn ask(input: &mut impl BufRead, output: &mut impl Write, question: &str) -> Result<Vec<String>> {
+    write!(output, "{question}: ")?;
+    output.flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer).context("unable to read answer")?;
+    Ok(answer.split_whitespace().map(str::to_owned).collect())
+}
+
+/// Asks for the "Contact" field, which is required, and the recommended fields which were not given as flags
+fn ask_missing(command: &GenerateCommand, input: &mut impl BufRead, output: &mut impl Write) -> Result<Answers> {
+    let mut answers = Answers::default();
+
+    while answers.contact.is_empty() {
+        answers.contact = ask(input, output, "Contact URIs, e.g., mailto:security@example.com")?;
+        if answers.contact.is_empty() && input.fill_buf()?.is_empty() {
+            anyhow::bail!("at least one contact is required");
+        }
+    }
+    if command.canonical.is_empty() {
+        answers.canonical = ask(input, output, "Canonical URIs of the file (optional)")?;
+    }
+    if command.policy.is_empty() {
+        answers.policy = ask(input, output, "Links to the security policy (optional)")?;
+    }
+    if command.encryption.is_empty() {
+        answers.encryption = ask(input, output, "Links to keys for encrypted communication (optional)")?;
+    }
+    if command.lang.is_none() {
+        let lang = ask(input, output, "Preferred languages, separated by commas (optional)")?;
+        answers.lang = (!lang.is_empty()).then(|| lang.concat());
+    }
+
+    Ok(answers)
+}
+
+pub fn generate(command: &GenerateCommand, options: &SecurityTxtOptions) -> Result<()> {
+    let mut builder = SecurityTxtBuilder::new();
+
+    // Without the required "Contact" field, the recommended fields are asked for when running in a terminal.
+    let answers = match command.contact.is_empty() && io::stdin().is_terminal() {
+        true => ask_missing(command, &mut io::stdin().lock(), &mut io::stderr())?,
+        false => Answers::default(),
+    };
+
+    for uri in command.contact.iter().chain(&answers.contact) {
+        builder = builder.contact(uri);
+    }
+    let expires = command
+        .expires
+        .unwrap_or_else(|| (Utc::now() + command.expires_in).trunc_subsecs(0));
+    builder = builder.expires(expires);
+    for uri in command.encryption.iter().chain(&answers.encryption) {
+        builder = builder.encryption(uri);
+    }
+    for uri in &command.acknowledgments {
+        builder = builder.acknowledgments(uri);
+    }
+    if let Some(lang) = command.lang.as_ref().or(answers.lang.as_ref()) {
+        let languages: Vec<&str> = lang.split(',').map(str::trim).collect();
+        builder = builder.preferred_languages(&languages);
+    }
+    for uri in command.canonical.iter().chain(&answers.canonical) {
+        builder = builder.canonical(uri);
+    }
+    for uri in command.policy.iter().chain(&answers.policy) {
+        builder = builder.policy(uri);
+    }
+    for uri in &command.hiring {
+        builder = builder.hiring(uri);
+    }
+    for uri in &command.csaf {
+        builder = builder.csaf(uri);
+    }
+    for (name, value) in &command.extension {
+        builder = builder.extension(name, value);
+    }
+
+    // The fields are validated in the same way as when parsing a file, so the output always parses.
+    let txt = builder.build_with(options).context("invalid fields")?;
+    let render_options = RenderOptions::new(command.comment.clone());
+
+    let text = match &command.key {
+        Some(path) => {
+            let key = load_key(path)?;
+            txt.sign_with(&key, &render_options).context("unable to sign file")?
+        }
+        None => txt.render_with(&render_options),
+    };
+
+    write_output(command.output.as_deref(), &text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn command(args: &[&str]) -> GenerateCommand {
+        argh::FromArgs::from_args(&["generate"], args).unwrap()
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("48h"), Ok(Duration::hours(48)));
+        assert_eq!(parse_duration("180d"), Ok(Duration::days(180)));
+        assert_eq!(parse_duration("12w"), Ok(Duration::weeks(12)));
+
+        assert!(parse_duration("d").unwrap_err().contains("expected a number"));
+        assert!(parse_duration("-1d").unwrap_err().contains("expected a number"));
+        assert!(parse_duration("180").unwrap_err().contains("invalid unit"));
+        assert!(parse_duration("1y").unwrap_err().contains("invalid unit"));
+    }
+
+    #[test]
+    fn test_parse_extension() {
+        assert_eq!(parse_extension("X-Foo=bar"), Ok(("X-Foo".to_owned(), "bar".to_owned())));
+        assert_eq!(
+            parse_extension(" X-Foo = a=b "),
+            Ok(("X-Foo".to_owned(), "a=b".to_owned()))
+        );
+        assert!(parse_extension("X-Foo").is_err());
+        assert!(parse_extension("=bar").is_err());
+
+        let command = command(&["--contact", "mailto:a@example.com", "--extension", "X-Foo=bar"]);
+        assert_eq!(command.extension, [("X-Foo".to_owned(), "bar".to_owned())]);
+    }
+
+    #[test]
+    fn test_ask_missing() {
+        let command = command(&["--policy", "https://example.com/policy"]);
+        let mut input =
+            Cursor::new("\nmailto:a@example.com https://example.com/contact\n\nhttps://example.com/key.asc\nen, de\n");
+        let mut output = vec![];
+
+        let answers = ask_missing(&command, &mut input, &mut output).unwrap();
+        assert_eq!(
+            answers,
+            Answers {
+                contact: vec![
+                    "mailto:a@example.com".to_owned(),
+                    "https://example.com/contact".to_owned()
+                ],
+                canonical: vec![],
+                policy: vec![],
+                encryption: vec!["https://example.com/key.asc".to_owned()],
+                lang: Some("en,de".to_owned()),
+            }
+        );
+
+        // The policy was given as a flag, so it is not asked for.
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("Contact URIs").count(), 2);
+        assert!(!output.contains("security policy"));
+    }
+
+    #[test]
+    fn test_ask_missing_eof() {
+        let command = command(&[]);
+        let err = ask_missing(&command, &mut Cursor::new(""), &mut vec![]).unwrap_err();
+        assert_eq!(err.to_string(), "at least one contact is required");
+    }
+}
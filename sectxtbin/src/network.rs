@@ -1,9 +1,62 @@
+use super::findings::ScanFinding;
 use anyhow::{Context, Result};
-use reqwest::Response;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use mime::Mime;
+use reqwest::header::{CONTENT_TYPE, LOCATION};
+use reqwest::redirect::Policy;
+use reqwest::{Client, Response};
 use sectxtlib::{SecurityTxt, SecurityTxtOptions};
+use std::time::Duration;
+use url::Url;
 
-pub fn is_file_present(result: Result<reqwest::Response, reqwest::Error>) -> Result<Response> {
-    let resp = result.context("HTTP request failed")?;
+/// The maximum number of redirects to follow for a single request
+const MAX_REDIRECTS: usize = 10;
+
+/// The number of bytes at the start of a body which are inspected to detect content other than a security.txt file
+const SNIFF_LEN: usize = 512;
+
+/// Creates an HTTP client which does not follow redirects by itself, see [`get`]
+pub fn client(timeout: u64) -> Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(timeout))
+        .redirect(Policy::none())
+        .build()
+        .unwrap()
+}
+
+/// Sends a GET request to the URL and follows redirects, recording every URL redirected to in `redirects`
+pub async fn get(client: &Client, url: &str, redirects: &mut Vec<String>) -> Result<Response> {
+    let mut url = Url::parse(url).context("unable to parse URL")?;
+
+    loop {
+        let resp = client.get(url.clone()).send().await.context("HTTP request failed")?;
+        if !resp.status().is_redirection() {
+            return Ok(resp);
+        }
+
+        if redirects.len() >= MAX_REDIRECTS {
+            anyhow::bail!("too many redirects");
+        }
+
+        let location = resp
+            .headers()
+            .get(LOCATION)
+            .context("HTTP redirect without location")?
+            .to_str()
+            .context("invalid HTTP redirect location")?;
+        let next = url.join(location).context("invalid HTTP redirect location")?;
+        redirects.push(next.to_string());
+
+        // The redirect is recorded nonetheless, so that it can be reported.
+        if url.scheme() == "https" && next.scheme() != "https" {
+            anyhow::bail!("HTTP redirect to insecure location");
+        }
+        url = next;
+    }
+}
+
+pub fn is_file_present(result: Result<Response>) -> Result<Response> {
+    let resp = result?;
 
     if resp.status() != reqwest::StatusCode::OK {
         anyhow::bail!("HTTP status code not OK");
@@ -12,17 +65,273 @@ pub fn is_file_present(result: Result<reqwest::Response, reqwest::Error>) -> Res
     Ok(resp)
 }
 
-pub async fn is_securitytxt(resp: Response, options: &SecurityTxtOptions) -> Result<SecurityTxt> {
-    if let Some(content_type) = resp.headers().get("Content-Type") {
-        let value: &str = content_type.to_str().context("error parsing HTTP body")?;
+/// Returns whether the data looks like the start of an HTML document
+fn is_html(data: &[u8]) -> bool {
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    let start = data.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(data.len());
+    let data = &data[start..];
+
+    ["<!doctype html", "<html", "<head", "<body", "<!--"]
+        .iter()
+        .any(|tag| data.len() >= tag.len() && data[..tag.len()].eq_ignore_ascii_case(tag.as_bytes()))
+}
+
+/// Returns whether the body is encoded as UTF-16 according to its byte order mark or the charset of its Content-Type
+fn is_utf16(content_type: Option<&str>, data: &[u8]) -> bool {
+    if data.starts_with(b"\xFF\xFE") || data.starts_with(b"\xFE\xFF") {
+        return true;
+    }
+
+    let mime = content_type.and_then(|value| value.parse::<Mime>().ok());
+    let charset = mime.as_ref().and_then(|mime| mime.get_param(mime::CHARSET));
+    let encoding = charset.and_then(|charset| Encoding::for_label(charset.as_str().as_bytes()));
+    encoding.is_some_and(|encoding| encoding == UTF_16LE || encoding == UTF_16BE)
+}
+
+// A security.txt file is plain text, so anything else is rejected before reading further. UTF-16 encodes ASCII
+// characters with NUL bytes, so these only indicate binary data in other encodings.
+fn sniff(data: &[u8], utf16: bool, url: &str, findings: &mut Vec<ScanFinding>) -> Result<()> {
+    if is_html(data) {
+        findings.push(ScanFinding::error(
+            "STXT207",
+            "HTTP body is an HTML document instead of a security.txt file".to_owned(),
+            url,
+        ));
+        anyhow::bail!("HTTP body is an HTML document");
+    }
+    if !utf16 && data.contains(&0) {
+        anyhow::bail!("HTTP body contains binary data");
+    }
+
+    Ok(())
+}
+
+/// Reads the body of a response, aborting as soon as it exceeds `max_size` bytes or turns out not to be text
+pub async fn read_body(mut resp: Response, max_size: usize, findings: &mut Vec<ScanFinding>) -> Result<Vec<u8>> {
+    let url = resp.url().to_string();
+    let content_type = resp
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+    let too_large = || anyhow::anyhow!("HTTP body exceeds {max_size} bytes");
+
+    if resp.content_length().is_some_and(|len| len > max_size as u64) {
+        return Err(too_large());
+    }
+
+    let mut body = vec![];
+    let mut sniffed = false;
+
+    while let Some(chunk) = resp.chunk().await.context("error reading HTTP body")? {
+        if body.len() + chunk.len() > max_size {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+
+        if !sniffed && body.len() >= SNIFF_LEN {
+            let utf16 = is_utf16(content_type.as_deref(), &body);
+            sniff(&body[..SNIFF_LEN], utf16, &url, findings)?;
+            sniffed = true;
+        }
+    }
+
+    if !sniffed {
+        sniff(&body, is_utf16(content_type.as_deref(), &body), &url, findings)?;
+    }
+
+    Ok(body)
+}
+
+/// Decodes the body of a response according to its Content-Type header
+///
+/// RFC 9116 requires the media type "text/plain" with the charset "utf-8". Deviations are reported as findings, but
+/// the body is decoded nonetheless: a missing charset defaults to UTF-8, and other charsets are used if they are known.
+/// A body which is not valid in its charset is rejected, where invalid UTF-8 is reported as a [`ParseError`] together
+/// with its location.
+///
+/// [`ParseError`]: sectxtlib::ParseError
+pub fn decode_body(
+    content_type: Option<&str>,
+    body: &[u8],
+    url: &str,
+    options: &SecurityTxtOptions,
+    findings: &mut Vec<ScanFinding>,
+) -> Result<String> {
+    let mime = match content_type {
+        Some(value) => match value.parse::<Mime>() {
+            Ok(mime) => Some(mime),
+            Err(_) => {
+                findings.push(ScanFinding::warning(
+                    "STXT203",
+                    format!("invalid HTTP content type {value:?}"),
+                    url,
+                ));
+                None
+            }
+        },
+        None => {
+            findings.push(ScanFinding::warning(
+                "STXT203",
+                "HTTP content type not specified".to_owned(),
+                url,
+            ));
+            None
+        }
+    };
+
+    if let Some(mime) = &mime {
+        if mime.type_() != mime::TEXT || mime.subtype() != mime::PLAIN {
+            findings.push(ScanFinding::warning(
+                "STXT204",
+                format!("HTTP content type is {} instead of text/plain", mime.essence_str()),
+                url,
+            ));
+        }
+    }
 
-        if value.starts_with("text/plain") && value.contains("charset=utf-8") {
-            let s = resp.text().await.context("error parsing HTTP body")?;
-            Ok(SecurityTxt::parse_with(&s, options)?)
-        } else {
-            anyhow::bail!("invalid HTTP content type");
+    let charset = mime.as_ref().and_then(|mime| mime.get_param(mime::CHARSET));
+    let encoding = match charset {
+        Some(charset) if charset == mime::UTF_8 => UTF_8,
+        Some(charset) => {
+            let encoding = Encoding::for_label(charset.as_str().as_bytes());
+            let message = match encoding {
+                Some(_) => format!("HTTP charset is {charset} instead of utf-8"),
+                None => format!("HTTP charset {charset} is unknown, decoding as utf-8"),
+            };
+            findings.push(ScanFinding::warning("STXT206", message, url));
+            encoding.unwrap_or(UTF_8)
+        }
+        None => {
+            if mime.is_some() {
+                findings.push(ScanFinding::warning(
+                    "STXT205",
+                    "HTTP charset not specified, decoding as utf-8".to_owned(),
+                    url,
+                ));
+            }
+            UTF_8
         }
-    } else {
-        anyhow::bail!("HTTP content type not specified");
+    };
+
+    if encoding == UTF_8 {
+        // The size of the body is already limited while reading it.
+        let options = SecurityTxtOptions {
+            max_size: None,
+            ..options.clone()
+        };
+        // A byte order mark is kept, so that it is reported as a deviation when diagnosing the file.
+        return Ok(SecurityTxt::decode_bytes(body, &options)?.into_owned());
+    }
+
+    match encoding.decode_without_bom_handling_and_without_replacement(body) {
+        Some(text) => Ok(text.into_owned()),
+        None => anyhow::bail!("HTTP body is not valid {}", encoding.name()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sectxtlib::ParseError;
+
+    const URL: &str = "https://example.com/.well-known/security.txt";
+    const FILE: &str = "Contact: mailto:security@example.com\n";
+
+    fn decode(content_type: Option<&str>, body: &[u8]) -> (Result<String>, Vec<&'static str>) {
+        let mut findings = vec![];
+        let result = decode_body(content_type, body, URL, &SecurityTxtOptions::new(false), &mut findings);
+        (result, findings.iter().map(|f| f.code).collect())
+    }
+
+    #[test]
+    fn test_is_html() {
+        assert!(is_html(b"<!DOCTYPE html>\n<html>"));
+        assert!(is_html(b"\xEF\xBB\xBF  \r\n<HTML lang=\"en\">"));
+        assert!(is_html(b"<!-- generated -->"));
+        assert!(!is_html(FILE.as_bytes()));
+        assert!(!is_html(b"# <html>\n"));
+        assert!(!is_html(b"<ht"));
+    }
+
+    #[test]
+    fn test_sniff() {
+        let mut findings = vec![];
+        assert!(sniff(FILE.as_bytes(), false, URL, &mut findings).is_ok());
+        assert!(findings.is_empty());
+
+        assert!(sniff(b"<html><body>Not found</body></html>", false, URL, &mut findings).is_err());
+        assert_eq!(findings.iter().map(|f| f.code).collect::<Vec<_>>(), ["STXT207"]);
+
+        findings.clear();
+        let err = sniff(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", false, URL, &mut findings).unwrap_err();
+        assert_eq!(err.to_string(), "HTTP body contains binary data");
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn test_sniff_utf16() {
+        let utf16le: Vec<u8> = FILE.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let with_bom = [&b"\xFF\xFE"[..], &utf16le].concat();
+
+        assert!(is_utf16(None, &with_bom));
+        assert!(is_utf16(Some("text/plain; charset=utf-16le"), &utf16le));
+        assert!(is_utf16(Some("text/plain; charset=UTF-16"), &utf16le));
+        assert!(!is_utf16(Some("text/plain; charset=utf-8"), &utf16le));
+        assert!(!is_utf16(None, FILE.as_bytes()));
+
+        let mut findings = vec![];
+        assert!(sniff(&utf16le, true, URL, &mut findings).is_ok());
+        assert!(sniff(&utf16le, false, URL, &mut findings).is_err());
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn test_decode_body_charset() {
+        let (text, codes) = decode(Some("text/plain; charset=utf-8"), FILE.as_bytes());
+        assert_eq!(text.unwrap(), FILE);
+        assert!(codes.is_empty());
+
+        let (text, codes) = decode(Some("text/plain"), FILE.as_bytes());
+        assert_eq!(text.unwrap(), FILE);
+        assert_eq!(codes, ["STXT205"]);
+
+        let (text, codes) = decode(None, FILE.as_bytes());
+        assert_eq!(text.unwrap(), FILE);
+        assert_eq!(codes, ["STXT203"]);
+
+        let (text, codes) = decode(Some("text/html; charset=iso-8859-1"), b"Hiring: caf\xE9\n");
+        assert_eq!(text.unwrap(), "Hiring: caf\u{E9}\n");
+        assert_eq!(codes, ["STXT204", "STXT206"]);
+
+        let (text, codes) = decode(Some("text/plain; charset=x-unknown"), FILE.as_bytes());
+        assert_eq!(text.unwrap(), FILE);
+        assert_eq!(codes, ["STXT206"]);
+    }
+
+    #[test]
+    fn test_decode_body_bom() {
+        let body = [b"\xEF\xBB\xBF", FILE.as_bytes()].concat();
+        let (text, _) = decode(Some("text/plain; charset=utf-8"), &body);
+        assert_eq!(text.unwrap(), format!("\u{FEFF}{FILE}"));
+
+        let body: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(FILE.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        let (text, _) = decode(Some("text/plain; charset=utf-16le"), &body);
+        assert_eq!(text.unwrap(), format!("\u{FEFF}{FILE}"));
+    }
+
+    #[test]
+    fn test_decode_body_invalid() {
+        let (result, _) = decode(Some("text/plain; charset=utf-8"), b"Contact: \xC3\x28\n");
+        let err = result.unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(err.code(), "STXT018");
+        assert_eq!(err.span().map(|s| (s.line, s.start)), Some((1, 9)));
+
+        let (result, _) = decode(Some("text/plain; charset=shift_jis"), b"Contact: \x81\n");
+        assert_eq!(result.unwrap_err().to_string(), "HTTP body is not valid Shift_JIS");
     }
 }
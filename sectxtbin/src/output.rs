@@ -0,0 +1,248 @@
+use super::record::Record;
+use anyhow::Result;
+use std::io::Write;
+use std::str::FromStr;
+
+/// The format in which records are printed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// A single JSON array containing all records
+    Json,
+    /// One JSON record per line
+    Ndjson,
+    /// Comma-separated values with a header line
+    Csv,
+    /// One human-readable line per record
+    Text,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Format::Json),
+            "ndjson" => Ok(Format::Ndjson),
+            "csv" => Ok(Format::Csv),
+            "text" => Ok(Format::Text),
+            _ => Err(format!("unknown format {s:?}, expected one of json, ndjson, csv, text")),
+        }
+    }
+}
+
+const CSV_HEADER: [&str; 9] = [
+    "version",
+    "domain",
+    "status",
+    "url",
+    "expires",
+    "contact",
+    "warnings",
+    "error",
+    "error_code",
+];
+
+enum Sink<W: Write> {
+    Json { writer: W, empty: bool },
+    Ndjson(W),
+    Csv(Box<csv::Writer<W>>),
+    Text(W),
+}
+
+/// Prints records to a writer such as standard output, independently of any logging
+///
+/// Every record is written as soon as it is available, including those in a JSON array.
+pub struct Output<W: Write> {
+    sink: Sink<W>,
+}
+
+impl<W: Write> Output<W> {
+    pub fn new(format: Format, mut writer: W) -> Result<Self> {
+        let sink = match format {
+            Format::Json => {
+                write!(writer, "[")?;
+                writer.flush()?;
+                Sink::Json { writer, empty: true }
+            }
+            Format::Ndjson => Sink::Ndjson(writer),
+            Format::Csv => {
+                let mut writer = csv::Writer::from_writer(writer);
+                writer.write_record(CSV_HEADER)?;
+                writer.flush()?;
+                Sink::Csv(Box::new(writer))
+            }
+            Format::Text => Sink::Text(writer),
+        };
+
+        Ok(Output { sink })
+    }
+
+    pub fn write(&mut self, record: &Record) -> Result<()> {
+        // The flat formats list the codes of lints and scan findings together.
+        let codes: Vec<&str> = record
+            .warnings
+            .iter()
+            .map(String::as_str)
+            .chain(record.findings.iter().map(|f| f.code))
+            .collect();
+
+        match &mut self.sink {
+            Sink::Json { writer, empty } => {
+                let separator = if *empty { "\n  " } else { ",\n  " };
+                writer.write_all(separator.as_bytes())?;
+                serde_json::to_writer(&mut *writer, record)?;
+                writer.flush()?;
+                *empty = false;
+            }
+            Sink::Ndjson(writer) => {
+                serde_json::to_writer(&mut *writer, record)?;
+                writeln!(writer)?;
+                writer.flush()?;
+            }
+            Sink::Csv(writer) => {
+                let expires = record.content.map(|txt| txt.expires.datetime.to_rfc3339());
+                let contact = record.content.map(|txt| {
+                    let uris: Vec<&str> = txt.contact.iter().map(|c| c.uri.as_str()).collect();
+                    uris.join(" ")
+                });
+
+                writer.write_record([
+                    &record.version.to_string(),
+                    record.domain,
+                    record.status,
+                    record.url.unwrap_or_default(),
+                    &expires.unwrap_or_default(),
+                    &contact.unwrap_or_default(),
+                    &codes.join(" "),
+                    record.error.unwrap_or_default(),
+                    record.error_code.unwrap_or_default(),
+                ])?;
+                writer.flush()?;
+            }
+            Sink::Text(writer) => {
+                let detail = match (record.url, record.error, record.error_code) {
+                    (_, Some(error), Some(code)) => format!("{code}: {error}"),
+                    (_, Some(error), None) => error.to_owned(),
+                    (Some(url), None, _) => url.to_owned(),
+                    (None, None, _) => String::new(),
+                };
+                let mut line = format!("{} {} {detail}", record.status, record.domain);
+                if !codes.is_empty() {
+                    line.push_str(&format!(" (warnings: {})", codes.join(", ")));
+                }
+                writeln!(writer, "{line}")?;
+                writer.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<()> {
+        if let Sink::Json { mut writer, empty } = self.sink {
+            match empty {
+                true => writeln!(writer, "]")?,
+                false => writeln!(writer, "\n]")?,
+            }
+            writer.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::findings::ScanFinding;
+    use crate::status::Status;
+    use sectxtlib::SecurityTxt;
+    use serde_json::Value;
+
+    const URL: &str = "https://example.com/.well-known/security.txt";
+
+    fn statuses() -> Vec<Status> {
+        let text = "Contact: mailto:security@example.com\nExpires: 2999-01-01T00:00:00Z\n";
+        let mut ok = Status::new("example.com".to_owned());
+        ok.available = true;
+        ok.url = Some(URL.to_owned());
+        ok.content = Some(SecurityTxt::parse(text).unwrap());
+        ok.warnings = vec!["STXT102".to_owned()];
+        ok.findings = vec![ScanFinding::warning(
+            "STXT205",
+            "HTTP charset not specified".to_owned(),
+            URL,
+        )];
+
+        let err = Status::error("example.org".to_owned(), &anyhow::anyhow!("HTTP status code not OK"));
+
+        vec![ok, err]
+    }
+
+    fn render(format: Format, statuses: &[Status]) -> String {
+        let mut buf = vec![];
+        let mut output = Output::new(format, &mut buf).unwrap();
+        for status in statuses {
+            output.write(&Record::from(status)).unwrap();
+        }
+        output.finish().unwrap();
+
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_json() {
+        let out = render(Format::Json, &statuses());
+        let records: Vec<Value> = serde_json::from_str(&out).unwrap();
+        assert!(out.starts_with("[\n  {"));
+        assert!(out.ends_with("}\n]\n"));
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["domain"], "example.com");
+        assert_eq!(records[0]["status"], "OK");
+        assert_eq!(
+            records[0]["content"]["contact"][0]["uri"],
+            "mailto:security@example.com"
+        );
+        assert_eq!(records[0]["findings"][0]["code"], "STXT205");
+        assert_eq!(records[1]["status"], "ERR");
+        assert_eq!(records[1]["content"], Value::Null);
+
+        assert_eq!(render(Format::Json, &[]), "[]\n");
+    }
+
+    #[test]
+    fn test_ndjson() {
+        let out = render(Format::Ndjson, &statuses());
+        let records: Vec<Value> = out.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["version"], 1);
+        assert_eq!(records[0]["warnings"][0], "STXT102");
+        assert_eq!(records[1]["error"], "HTTP status code not OK");
+        assert_eq!(records[1]["verification"], Value::Null);
+
+        let json: Vec<Value> = serde_json::from_str(&render(Format::Json, &statuses())).unwrap();
+        assert_eq!(records, json);
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            render(Format::Csv, &statuses()),
+            "version,domain,status,url,expires,contact,warnings,error,error_code\n\
+            1,example.com,OK,https://example.com/.well-known/security.txt,2999-01-01T00:00:00+00:00,\
+            mailto:security@example.com,STXT102 STXT205,,\n\
+            1,example.org,ERR,,,,,HTTP status code not OK,\n"
+        );
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(
+            render(Format::Text, &statuses()),
+            "OK example.com https://example.com/.well-known/security.txt (warnings: STXT102, STXT205)\n\
+            ERR example.org HTTP status code not OK\n"
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use super::findings::ScanFinding;
+use super::status::{Attempt, Status};
+use sectxtlib::{SecurityTxt, SignatureVerification};
+use serde::Serialize;
+
+/// The version of the record format, which is incremented on every incompatible change
+///
+/// The format is described by the JSON Schema in `schema/record.schema.json`.
+pub const RECORD_VERSION: u32 = 1;
+
+/// The result of checking a single domain, as printed by `sectxt scan`
+#[derive(Serialize)]
+pub struct Record<'a> {
+    pub version: u32,
+    pub domain: &'a str,
+    pub status: &'a str,
+    pub url: Option<&'a str>,
+    pub content: Option<&'a SecurityTxt>,
+    pub warnings: &'a [String],
+    pub error: Option<&'a str>,
+    pub error_code: Option<&'a str>,
+    pub attempts: &'a [Attempt],
+    pub findings: &'a [ScanFinding],
+    pub verification: Option<&'a SignatureVerification>,
+}
+
+impl<'a> From<&'a Status> for Record<'a> {
+    fn from(status: &'a Status) -> Self {
+        Record {
+            version: RECORD_VERSION,
+            domain: &status.domain,
+            status: if status.available { "OK" } else { "ERR" },
+            url: status.url.as_deref(),
+            content: status.content.as_ref(),
+            warnings: &status.warnings,
+            error: status.error.as_deref(),
+            error_code: status.error_code,
+            attempts: &status.attempts,
+            findings: &status.findings,
+            verification: status.verification.as_ref(),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use super::network::client;
+use super::output::Output;
+use super::record::Record;
+use super::settings::ScanCommand;
+use super::status::Status;
+use super::verify::load_keyring;
+use super::website::{normalize_input, Website};
+use anyhow::Result;
+use futures::channel::mpsc::channel;
+use futures::{Stream, StreamExt};
+use reqwest::Client;
+use sectxtlib::{Keyring, SecurityTxtOptions};
+use std::io::BufRead;
+use tracing::debug;
+
+fn stdin(threads: usize) -> impl Stream<Item = String> {
+    let (mut tx, rx) = channel(threads);
+
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines().map_while(Result::ok) {
+            loop {
+                let status = tx.try_send(line.to_owned());
+
+                match status {
+                    Err(e) if e.is_full() => continue,
+                    _ => break,
+                }
+            }
+        }
+    });
+
+    rx
+}
+
+async fn process_line(
+    line: String,
+    client: &Client,
+    options: &SecurityTxtOptions,
+    s: &ScanCommand,
+    keyring: Option<&Keyring>,
+) -> Status {
+    let line = normalize_input(&line);
+    let website = Website::try_from(&line[..]);
+
+    match website {
+        Ok(website) => {
+            website
+                .get_status(client, options, s.max_body_size, keyring, s.fetch_keys)
+                .await
+        }
+        Err(e) => Status::error(line, &e),
+    }
+}
+
+#[tokio::main]
+async fn process_domains(s: &ScanCommand, options: &SecurityTxtOptions) -> Result<(u64, u64)> {
+    let client = client(s.timeout);
+    // Signatures are only verified if there are keys to verify them against.
+    let keyring = match !s.key.is_empty() || s.fetch_keys {
+        true => Some(load_keyring(&s.key)?),
+        false => None,
+    };
+
+    let mut statuses = stdin(s.threads)
+        .map(|input| {
+            let client = &client;
+            let keyring = keyring.as_ref();
+            async move { process_line(input, client, options, s, keyring).await }
+        })
+        .buffer_unordered(s.threads);
+
+    let mut output = Output::new(s.format, std::io::stdout())?;
+    let mut count: (u64, u64) = (0, 0);
+
+    while let Some(status) = statuses.next().await {
+        debug!(domain = &status.domain, available = status.available);
+
+        count.0 += 1;
+        if status.available {
+            count.1 += 1;
+        }
+
+        if status.available || !s.quiet {
+            output.write(&Record::from(&status))?;
+        }
+    }
+
+    output.finish()?;
+
+    Ok(count)
+}
+
+pub fn scan(command: &ScanCommand, options: &SecurityTxtOptions) -> Result<()> {
+    let count = process_domains(command, options)?;
+
+    if command.print_stats {
+        println!("{}/{}", count.0, count.1);
+    }
+
+    Ok(())
+}
@@ -1,8 +1,38 @@
+use super::generate::{parse_duration, parse_extension};
+use super::output::Format;
 use argh::FromArgs;
+use chrono::{DateTime, Duration, Utc};
+use std::path::PathBuf;
 
 #[derive(FromArgs)]
 /// A tool for working with security.txt files.
 pub struct Settings {
+    /// whether to be strict with line endings or more relaxed
+    #[argh(switch)]
+    pub strict: bool,
+
+    #[argh(subcommand)]
+    pub command: Command,
+}
+
+// The command is parsed only once, so the size of its variants does not matter.
+#[allow(clippy::large_enum_variant)]
+#[derive(FromArgs)]
+#[argh(subcommand)]
+pub enum Command {
+    Scan(ScanCommand),
+    Check(CheckCommand),
+    Fetch(FetchCommand),
+    Diff(DiffCommand),
+    Generate(GenerateCommand),
+    Sign(SignCommand),
+    Explain(ExplainCommand),
+}
+
+#[derive(FromArgs)]
+/// Check the security.txt files of domains read from standard input.
+#[argh(subcommand, name = "scan")]
+pub struct ScanCommand {
     /// number of simultaneous domains to process
     #[argh(option, default = "30")]
     pub threads: usize,
@@ -11,15 +41,162 @@ pub struct Settings {
     #[argh(option, default = "3")]
     pub timeout: u64,
 
-    /// whether to be strict with line endings or more relaxed
-    #[argh(switch)]
-    pub strict: bool,
+    /// maximum size of a security.txt file in bytes
+    #[argh(option, default = "1024 * 1024")]
+    pub max_body_size: usize,
 
     /// only print domains for which the run was successful
     #[argh(switch, short = 'q')]
     pub quiet: bool,
 
+    /// output format of the results: json, ndjson, csv, or text
+    #[argh(option, default = "Format::Ndjson")]
+    pub format: Format,
+
     /// print statistics before exit
     #[argh(switch)]
     pub print_stats: bool,
+
+    /// path to an ASCII-armored public key to verify signatures against
+    #[argh(option)]
+    pub key: Vec<PathBuf>,
+
+    /// verify signatures also against the keys linked in the "Encryption" fields of each file
+    #[argh(switch)]
+    pub fetch_keys: bool,
+}
+
+#[derive(FromArgs)]
+/// Validate local security.txt files.
+#[argh(subcommand, name = "check")]
+pub struct CheckCommand {
+    /// path to an ASCII-armored public key to verify signatures against
+    #[argh(option)]
+    pub key: Vec<PathBuf>,
+
+    /// paths to the security.txt files to check, or standard input if none are given
+    #[argh(positional)]
+    pub inputs: Vec<PathBuf>,
+}
+
+#[derive(FromArgs)]
+/// Explain the codes of errors, lints, and scan findings.
+#[argh(subcommand, name = "explain")]
+pub struct ExplainCommand {
+    /// the code to explain, e.g., "STXT001", or all codes if not given
+    #[argh(positional)]
+    pub code: Option<String>,
+}
+
+#[derive(FromArgs)]
+/// Sign a security.txt file with an OpenPGP key.
+#[argh(subcommand, name = "sign")]
+pub struct SignCommand {
+    /// path to the ASCII-armored secret key
+    #[argh(option)]
+    pub key: PathBuf,
+
+    /// path to write the signed file to instead of standard output
+    #[argh(option, short = 'o')]
+    pub output: Option<PathBuf>,
+
+    /// path to the security.txt file to sign, or standard input if not given
+    #[argh(positional)]
+    pub input: Option<PathBuf>,
+}
+
+#[derive(FromArgs)]
+/// Download the security.txt file of a domain.
+#[argh(subcommand, name = "fetch")]
+pub struct FetchCommand {
+    /// seconds to wait before giving up
+    #[argh(option, default = "3")]
+    pub timeout: u64,
+
+    /// maximum size of a security.txt file in bytes
+    #[argh(option, default = "1024 * 1024")]
+    pub max_body_size: usize,
+
+    /// path to write the file to instead of standard output
+    #[argh(option, short = 'o')]
+    pub output: Option<PathBuf>,
+
+    /// the domain or URL of the website
+    #[argh(positional)]
+    pub domain: String,
+}
+
+#[derive(FromArgs)]
+/// Show the fields which differ between two security.txt files.
+#[argh(subcommand, name = "diff")]
+pub struct DiffCommand {
+    /// path to the old security.txt file
+    #[argh(positional)]
+    pub old: PathBuf,
+
+    /// path to the new security.txt file
+    #[argh(positional)]
+    pub new: PathBuf,
+}
+
+#[derive(FromArgs)]
+/// Generate a security.txt file from the given fields.
+#[argh(subcommand, name = "generate")]
+pub struct GenerateCommand {
+    /// a URI to contact for reporting vulnerabilities, in order of preference, or asked for if not given and running
+    /// in a terminal
+    #[argh(option)]
+    pub contact: Vec<String>,
+
+    /// the date and time after which the file is considered stale
+    #[argh(option)]
+    pub expires: Option<DateTime<Utc>>,
+
+    /// the time until the file is considered stale, e.g., "180d", "12w", or "48h"
+    #[argh(option, from_str_fn(parse_duration), default = "Duration::days(180)")]
+    pub expires_in: Duration,
+
+    /// a link to a key for encrypted communication
+    #[argh(option)]
+    pub encryption: Vec<String>,
+
+    /// a link to a page where security researchers are recognized
+    #[argh(option)]
+    pub acknowledgments: Vec<String>,
+
+    /// the preferred languages for security reports, separated by commas
+    #[argh(option)]
+    pub lang: Option<String>,
+
+    /// the canonical URI of the file
+    #[argh(option)]
+    pub canonical: Vec<String>,
+
+    /// a link to the security policy
+    #[argh(option)]
+    pub policy: Vec<String>,
+
+    /// a link to security-related job positions
+    #[argh(option)]
+    pub hiring: Vec<String>,
+
+    /// a link to the CSAF provider metadata
+    #[argh(option)]
+    pub csaf: Vec<String>,
+
+    /// a field not defined by RFC 9116, given as "name=value"
+    #[argh(option, from_str_fn(parse_extension))]
+    pub extension: Vec<(String, String)>,
+
+    /// a comment to place at the top of the file
+    #[argh(option)]
+    pub comment: Vec<String>,
+
+    /// path to an ASCII-armored secret key to sign the file with
+    #[argh(option)]
+    pub key: Option<PathBuf>,
+
+    /// path to write the file to instead of standard output
+    #[argh(option, short = 'o')]
+    pub output: Option<PathBuf>,
 }
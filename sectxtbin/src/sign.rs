@@ -0,0 +1,39 @@
+use super::files::{read_input, write_output};
+use super::settings::SignCommand;
+use anyhow::{Context, Result};
+use sectxtlib::{SecurityTxt, SecurityTxtOptions, SigningKey};
+use std::fs;
+use std::path::Path;
+
+pub fn load_key(path: &Path) -> Result<SigningKey> {
+    let key = fs::read_to_string(path).with_context(|| format!("unable to read {}", path.display()))?;
+    SigningKey::from_armored(&key).context("unable to load secret key")
+}
+
+pub fn sign(command: &SignCommand, options: &SecurityTxtOptions) -> Result<()> {
+    let text = read_input(command.input.as_deref().unwrap_or(Path::new("-")))?;
+    let txt = SecurityTxt::parse_with(&text, options).context("input is not a valid security.txt file")?;
+    if txt.signature.is_some() {
+        anyhow::bail!("input is already signed");
+    }
+
+    let key = load_key(&command.key)?;
+
+    // Input which conforms to the strict grammar is signed as is, so that comments and field order are preserved.
+    // Otherwise, deviations such as a byte order mark or bare CR line endings would end up in the signed text, so the
+    // parsed file is rendered and signed instead.
+    let strict = SecurityTxtOptions {
+        strict: true,
+        ..options.clone()
+    };
+    let signed = match SecurityTxt::parse_with(&text, &strict) {
+        Ok(_) => key.sign(&text),
+        Err(_) => {
+            eprintln!("warning: input does not conform to the strict grammar, signing the normalized file instead");
+            txt.sign(&key)
+        }
+    };
+    let signed = signed.context("unable to sign input")?;
+
+    write_output(command.output.as_deref(), &signed)
+}
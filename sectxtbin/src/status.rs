@@ -1,4 +1,84 @@
+use super::findings::ScanFinding;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Response;
+use sectxtlib::{SecurityTxt, SignatureVerification};
+use serde::Serialize;
+
+/// The outcome of requesting a single location of the security.txt file
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Attempt {
+    /// The URL which was requested first
+    pub url: String,
+
+    /// The URLs which were redirected to, in order
+    pub redirects: Vec<String>,
+
+    /// The HTTP status code of the final response
+    pub status: Option<u16>,
+
+    /// The value of the Content-Type header of the final response
+    pub content_type: Option<String>,
+
+    /// The size of the body in bytes, if it was read
+    pub body_size: Option<usize>,
+
+    /// The time taken in milliseconds, including redirects and reading the body
+    pub latency_ms: u64,
+
+    /// Why no valid security.txt file was found at this location
+    pub error: Option<String>,
+}
+
+impl Attempt {
+    pub fn new(url: &str) -> Self {
+        Attempt {
+            url: url.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    pub fn record_response(&mut self, resp: &Response) {
+        self.status = Some(resp.status().as_u16());
+        self.content_type = resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .map(|v| String::from_utf8_lossy(v.as_bytes()).into_owned());
+    }
+}
+
 pub struct Status {
     pub domain: String,
     pub available: bool,
+    pub url: Option<String>,
+    pub content: Option<SecurityTxt>,
+    pub warnings: Vec<String>,
+    pub error: Option<String>,
+    pub error_code: Option<&'static str>,
+    pub attempts: Vec<Attempt>,
+    pub findings: Vec<ScanFinding>,
+    pub verification: Option<SignatureVerification>,
+}
+
+impl Status {
+    pub fn new(domain: String) -> Self {
+        Status {
+            domain,
+            available: false,
+            url: None,
+            content: None,
+            warnings: vec![],
+            error: None,
+            error_code: None,
+            attempts: vec![],
+            findings: vec![],
+            verification: None,
+        }
+    }
+
+    pub fn error(domain: String, error: &anyhow::Error) -> Self {
+        Status {
+            error: Some(error.to_string()),
+            ..Status::new(domain)
+        }
+    }
 }
@@ -0,0 +1,114 @@
+use super::findings::ScanFinding;
+use super::network::{get, is_file_present};
+use anyhow::{Context, Result};
+use reqwest::Client;
+use sectxtlib::{Keyring, SecurityTxt, SecurityTxtOptions, SignatureError, SignatureValidity, SignatureVerification};
+use std::fs;
+use std::path::PathBuf;
+use url::Url;
+
+/// Loads the ASCII-armored public keys at the given paths into a single keyring
+pub fn load_keyring(paths: &[PathBuf]) -> Result<Keyring> {
+    let mut keyring = Keyring::new();
+
+    for path in paths {
+        let text = fs::read_to_string(path).with_context(|| format!("unable to read {}", path.display()))?;
+        keyring
+            .add_armored(&text)
+            .with_context(|| format!("unable to load key from {}", path.display()))?;
+    }
+
+    Ok(keyring)
+}
+
+/// Verifies the signature of a file, and reports a signature which cannot be trusted as a finding
+///
+/// Returns `None` if the file is not signed or the signature cannot be verified at all.
+pub fn verify_signature(
+    keyring: &Keyring,
+    text: &str,
+    options: &SecurityTxtOptions,
+    url: &str,
+    findings: &mut Vec<ScanFinding>,
+) -> Option<SignatureVerification> {
+    let verification = match keyring.verify(text, options) {
+        Ok(verification) => verification,
+        Err(SignatureError::NotSigned) => return None,
+        Err(err) => {
+            findings.push(ScanFinding::error(
+                "STXT210",
+                format!("signature cannot be verified: {err}"),
+                url,
+            ));
+            return None;
+        }
+    };
+
+    let signer = verification
+        .fingerprint
+        .as_deref()
+        .or(verification.key_id.as_deref())
+        .unwrap_or("an unspecified key");
+    match verification.validity {
+        SignatureValidity::Valid => {}
+        SignatureValidity::Invalid => findings.push(ScanFinding::error(
+            "STXT208",
+            format!("signature by {signer} does not match the file"),
+            url,
+        )),
+        SignatureValidity::UnknownKey => findings.push(ScanFinding::warning(
+            "STXT209",
+            format!("signature was made by unknown key {signer}"),
+            url,
+        )),
+    }
+
+    Some(verification)
+}
+
+/// Adds the keys linked in the "Encryption" fields of a file to the keyring
+///
+/// Only keys served over HTTPS are loaded, either ASCII-armored or as binary packets. Keys referenced otherwise, e.g.,
+/// by an "openpgp4fpr" URI, would have to be looked up on a key server, and are skipped.
+pub async fn fetch_keys(
+    client: &Client,
+    txt: &SecurityTxt,
+    keyring: &mut Keyring,
+    max_body_size: usize,
+    findings: &mut Vec<ScanFinding>,
+) {
+    for field in &txt.encryption {
+        let url = field.uri.as_str();
+        if Url::parse(url).map_or(true, |url| url.scheme() != "https") {
+            continue;
+        }
+
+        if let Err(err) = fetch_key(client, url, keyring, max_body_size).await {
+            findings.push(ScanFinding::warning(
+                "STXT211",
+                format!("unable to load key from the Encryption field: {err:#}"),
+                url,
+            ));
+        }
+    }
+}
+
+async fn fetch_key(client: &Client, url: &str, keyring: &mut Keyring, max_body_size: usize) -> Result<()> {
+    let mut response = is_file_present(get(client, url, &mut vec![]).await)?;
+
+    // Keys may be binary, so the body is not sniffed like that of a security.txt file.
+    let mut body = vec![];
+    while let Some(chunk) = response.chunk().await.context("error reading HTTP body")? {
+        if body.len() + chunk.len() > max_body_size {
+            anyhow::bail!("HTTP body exceeds {max_body_size} bytes");
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    match std::str::from_utf8(&body) {
+        Ok(text) if text.contains("-----BEGIN PGP PUBLIC KEY BLOCK-----") => keyring.add_armored(text)?,
+        _ => keyring.add_bytes(&body)?,
+    }
+
+    Ok(())
+}
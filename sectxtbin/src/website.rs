@@ -1,61 +1,195 @@
-use super::network::{is_file_present, is_securitytxt};
-use super::status::Status;
+use super::findings::{redirect_findings, ScanFinding};
+use super::network::{decode_body, get, is_file_present, read_body};
+use super::status::{Attempt, Status};
+use super::verify::{self, verify_signature};
 use anyhow::{Context, Result};
-use sectxtlib::SecurityTxtOptions;
+use sectxtlib::{Keyring, LintOptions, ParseError, SecurityTxt, SecurityTxtOptions};
 use std::convert::TryFrom;
-use tracing::info;
+use std::fmt;
+use std::time::Instant;
 use url::Url;
-use valuable::Valuable;
 
 pub struct Website {
     pub domain: String,
     pub urls: Vec<String>,
 }
 
-impl Website {
-    fn make_status(&self, available: bool) -> Status {
-        Status {
-            domain: self.domain.to_owned(),
-            available,
+/// Turns a domain or URL given by the user into a URL
+pub fn normalize_input(input: &str) -> String {
+    let input = input.trim().to_lowercase();
+    match input.starts_with("http") {
+        true => input,
+        false => format!("https://{input}"),
+    }
+}
+
+/// Parses a file, which was served from `served`, and returns it together with the codes of its warnings
+///
+/// The warnings are the deviations from the strict grammar, which are only reported by diagnostics as parsing succeeds
+/// despite them under the lenient grammar, followed by the lints.
+fn parse(text: &str, served: &str, options: &SecurityTxtOptions) -> Result<(SecurityTxt, Vec<String>), ParseError> {
+    let diagnostics = SecurityTxt::diagnose(text, options);
+    let mut warnings: Vec<String> = diagnostics.warnings().map(|f| f.code().to_owned()).collect();
+    let txt = diagnostics.into_result()?;
+
+    // The "Canonical" fields should list the location the file was eventually served from.
+    let lints = txt.lint_with(&LintOptions::new(Some(served.to_owned())));
+    warnings.extend(lints.iter().map(|l| l.code().to_owned()));
+
+    Ok((txt, warnings))
+}
+
+/// Why a single location did not yield a security.txt file
+#[derive(Debug)]
+enum LocationError {
+    /// The location does not exist or cannot be reached, so the next location is tried
+    Missing(anyhow::Error),
+
+    /// The location exists, but does not serve a valid security.txt file
+    Invalid(anyhow::Error),
+}
+
+impl fmt::Display for LocationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LocationError::Missing(err) | LocationError::Invalid(err) => write!(f, "{err}"),
         }
     }
+}
 
-    pub async fn get_status(&self, client: &reqwest::Client, options: &SecurityTxtOptions, quiet: bool) -> Status {
+impl Website {
+    /// Downloads the first security.txt file found, and returns its URL together with its content
+    pub async fn fetch(
+        &self,
+        client: &reqwest::Client,
+        options: &SecurityTxtOptions,
+        max_body_size: usize,
+        findings: &mut Vec<ScanFinding>,
+    ) -> Result<(String, String)> {
         let mut first_error: Option<anyhow::Error> = None;
 
         for url in &self.urls {
-            let response = client.get(&url[..]).send().await;
-
-            match is_file_present(response) {
-                Ok(response) => match is_securitytxt(response, options).await {
-                    Ok(txt) => {
-                        // Location exists and file is parsable.
-                        info!(domain = self.domain, content = txt.as_value(), status = "OK");
-                        return self.make_status(true);
-                    }
-                    Err(err) => {
-                        // Location exists but file is not parsable.
-                        if !quiet {
-                            info!(domain = self.domain, error = err.to_string(), status = "ERR");
+            let (_, result) = Self::fetch_attempt(client, url, options, max_body_size, findings).await;
+
+            match result {
+                Ok(text) => return Ok((url.to_owned(), text)),
+                Err(LocationError::Invalid(err)) => return Err(err),
+                Err(LocationError::Missing(err)) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+
+        Err(first_error.unwrap()) // self.urls is never empty
+    }
+
+    /// Requests a single location, and returns the decoded body together with a record of the attempt
+    ///
+    /// Findings about the redirect chain and the response are added to `findings`.
+    async fn fetch_attempt(
+        client: &reqwest::Client,
+        url: &str,
+        options: &SecurityTxtOptions,
+        max_body_size: usize,
+        findings: &mut Vec<ScanFinding>,
+    ) -> (Attempt, Result<String, LocationError>) {
+        let mut attempt = Attempt::new(url);
+        let start = Instant::now();
+        let result = Self::read(client, &mut attempt, options, max_body_size, findings).await;
+        attempt.latency_ms = start.elapsed().as_millis() as u64;
+        findings.extend(redirect_findings(&attempt));
+
+        if let Err(err) = &result {
+            attempt.error = Some(err.to_string());
+        }
+
+        (attempt, result)
+    }
+
+    async fn read(
+        client: &reqwest::Client,
+        attempt: &mut Attempt,
+        options: &SecurityTxtOptions,
+        max_body_size: usize,
+        findings: &mut Vec<ScanFinding>,
+    ) -> Result<String, LocationError> {
+        let response = get(client, &attempt.url, &mut attempt.redirects).await;
+        if let Ok(response) = &response {
+            attempt.record_response(response);
+        }
+        let response = is_file_present(response).map_err(LocationError::Missing)?;
+
+        let url = response.url().to_string();
+        let body = read_body(response, max_body_size, findings)
+            .await
+            .map_err(LocationError::Invalid)?;
+        attempt.body_size = Some(body.len());
+
+        decode_body(attempt.content_type.as_deref(), &body, &url, options, findings).map_err(LocationError::Invalid)
+    }
+
+    /// Checks the security.txt file of the website
+    ///
+    /// If a keyring is given, the signature of a signed file is verified against it, and, if `fetch_keys` is set,
+    /// against the keys linked in the "Encryption" fields of the file.
+    pub async fn get_status(
+        &self,
+        client: &reqwest::Client,
+        options: &SecurityTxtOptions,
+        max_body_size: usize,
+        keyring: Option<&Keyring>,
+        fetch_keys: bool,
+    ) -> Status {
+        let mut status = Status::new(self.domain.to_owned());
+        let mut first_error: Option<LocationError> = None;
+
+        for url in &self.urls {
+            let (mut attempt, result) =
+                Self::fetch_attempt(client, url, options, max_body_size, &mut status.findings).await;
+            let served = attempt.redirects.last().unwrap_or(url).to_owned();
+            let result = result.and_then(|text| match parse(&text, &served, options) {
+                Ok((txt, warnings)) => Ok((text, txt, warnings)),
+                Err(err) => {
+                    attempt.error = Some(err.to_string());
+                    Err(LocationError::Invalid(err.into()))
+                }
+            });
+
+            match result {
+                Ok((text, txt, warnings)) => {
+                    // Location exists and file is parsable.
+                    status.warnings = warnings;
+                    if let Some(keyring) = keyring {
+                        let mut keyring = keyring.clone();
+                        if fetch_keys {
+                            verify::fetch_keys(client, &txt, &mut keyring, max_body_size, &mut status.findings).await;
                         }
-                        return self.make_status(false);
+                        status.verification = verify_signature(&keyring, &text, options, url, &mut status.findings);
                     }
-                },
+                    status.attempts.push(attempt);
+                    status.available = true;
+                    status.url = Some(url.to_owned());
+                    status.content = Some(txt);
+                    return status;
+                }
+                Err(LocationError::Invalid(err)) => {
+                    // Location exists but file is not parsable.
+                    status.attempts.push(attempt);
+                    status.url = Some(url.to_owned());
+                    status.error = Some(err.to_string());
+                    status.error_code = err.downcast_ref::<ParseError>().map(ParseError::code);
+                    return status;
+                }
                 Err(err) => {
-                    // Location does not exists.
-                    if first_error.is_none() {
-                        first_error = Some(err);
-                    }
+                    // Location does not exist.
+                    status.attempts.push(attempt);
+                    first_error.get_or_insert(err);
                 }
             }
         }
 
-        if !quiet {
-            let err = first_error.unwrap(); // self.urls is never empty
-            info!(domain = self.domain, error = err.to_string(), status = "ERR");
-        }
-
-        self.make_status(false)
+        status.error = first_error.map(|err| err.to_string());
+        status
     }
 }
 
@@ -75,3 +209,37 @@ impl TryFrom<&str> for Website {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, SecondsFormat, Utc};
+
+    #[test]
+    fn test_parse_warnings() {
+        let url = "https://example.com/.well-known/security.txt";
+        let expires = (Utc::now() + Duration::days(30)).to_rfc3339_opts(SecondsFormat::Secs, true);
+        let text = format!("\u{FEFF}Contact:\tmailto:security@example.com\nExpires: {expires}\nCanonical: {url}\n");
+        let options = SecurityTxtOptions::new(false);
+        let (txt, warnings) = parse(&text, url, &options).unwrap();
+
+        assert_eq!(txt, SecurityTxt::parse_with(&text, &options).unwrap());
+        assert_eq!(warnings, ["STXT013", "STXT016", "STXT105"]);
+        assert!(parse(&text, url, &SecurityTxtOptions::new(true)).is_err());
+    }
+
+    #[test]
+    fn test_parse_canonical() {
+        let url = "https://example.com/.well-known/security.txt";
+        let expires = (Utc::now() + Duration::days(30)).to_rfc3339_opts(SecondsFormat::Secs, true);
+        let text = format!("Contact: mailto:security@example.com\nExpires: {expires}\nCanonical: {url}\n");
+        let options = SecurityTxtOptions::new(true);
+
+        // The file was served from another location after a redirect.
+        let served = "https://www.example.com/security.txt";
+        assert_eq!(parse(&text, served, &options).unwrap().1, ["STXT103", "STXT105"]);
+
+        let text = text.replace(&format!("Canonical: {url}\n"), "");
+        assert_eq!(parse(&text, url, &options).unwrap().1, ["STXT102", "STXT105"]);
+    }
+}
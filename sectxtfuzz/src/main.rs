@@ -1,10 +1,9 @@
 use afl::*;
-use sectxtlib::SecurityTxt;
+use sectxtlib::{SecurityTxt, SecurityTxtOptions};
 
 fn main() {
     fuzz!(|data: &[u8]| {
-        if let Ok(s) = std::str::from_utf8(data) {
-            let _ = s.parse::<SecurityTxt>();
-        }
+        let _ = SecurityTxt::parse_bytes(data);
+        let _ = SecurityTxt::parse_bytes_with(data, &SecurityTxtOptions::new(false));
     });
 }
@@ -0,0 +1,92 @@
+//! Compares the heap allocations of parsing into a `SecurityTxt` with those of parsing into a `SecurityTxtRef`.
+//!
+//! Run with `cargo bench --bench allocations`.
+
+use sectxtlib::{ExpiryPolicy, SecurityTxt, SecurityTxtOptions, SecurityTxtRef};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Returns the number of allocations and the number of bytes allocated by `f`
+fn count<R>(f: impl FnOnce() -> R) -> (usize, usize) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = BYTES.load(Ordering::Relaxed);
+    let result = f();
+    let counts = (
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        BYTES.load(Ordering::Relaxed) - bytes,
+    );
+    drop(result);
+    counts
+}
+
+fn corpus() -> Vec<(String, String)> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("resources/test");
+    let mut files = vec![];
+
+    for category in ["valid_unsigned", "valid_signed"] {
+        for entry in fs::read_dir(dir.join(category)).unwrap() {
+            let path = entry.unwrap().path();
+            let name = format!("{category}/{}", path.file_name().unwrap().to_string_lossy());
+            files.push((name, fs::read_to_string(path).unwrap()));
+        }
+    }
+    files.sort();
+
+    let mut synthetic = String::from("Expires: 2999-01-01T00:00:00.000Z\n");
+    for i in 0..1000 {
+        synthetic.push_str(&format!(
+            "Contact: https://example.com/contact/{i}\nX-Field-{i}: value {i}\n"
+        ));
+    }
+    files.push(("synthetic (2001 fields)".to_owned(), synthetic));
+
+    files
+}
+
+fn main() {
+    let options = SecurityTxtOptions {
+        expiry: ExpiryPolicy::Ignore,
+        ..Default::default()
+    };
+
+    println!(
+        "{:<40} {:>14} {:>14} {:>14} {:>14}",
+        "file", "owned allocs", "owned bytes", "ref allocs", "ref bytes"
+    );
+    for (name, text) in corpus() {
+        let owned = count(|| SecurityTxt::parse_with(&text, &options).unwrap());
+        let borrowed = count(|| SecurityTxtRef::parse_with(&text, &options).unwrap());
+        println!(
+            "{name:<40} {:>14} {:>14} {:>14} {:>14}",
+            owned.0, owned.1, borrowed.0, borrowed.1
+        );
+    }
+}
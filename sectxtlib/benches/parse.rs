@@ -0,0 +1,123 @@
+//! Measures the parsing throughput on the test corpus and on large synthetic files.
+//!
+//! Run with `cargo bench --bench parse`. Pass a filter to run a subset, e.g., `cargo bench --bench parse -- synthetic`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use sectxtlib::{ExpiryPolicy, SecurityTxt, SecurityTxtOptions, SecurityTxtRef};
+use std::fs;
+use std::hint::black_box;
+use std::path::PathBuf;
+
+const SIGNATURE_DATA: &str = "iHUEARYKAB0WIQSsP2kEdoKDVFpSg6u3rK+YCkjapwUCY9qRaQAKCRC3rK+YCkja
+pwALAP9LEHSYMDW4h8QRHg4MwCzUdnbjBLIvpq4QTo3dIqCUPwEA31MsEf95OKCh
+MTHYHajOzjwpwlQVrjkK419igx4imgk=
+=KONn
+";
+
+fn options() -> SecurityTxtOptions {
+    SecurityTxtOptions {
+        expiry: ExpiryPolicy::Ignore,
+        ..Default::default()
+    }
+}
+
+fn category(name: &str) -> Vec<String> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("resources/test")
+        .join(name);
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| fs::read_to_string(path).unwrap())
+        .collect()
+}
+
+/// Returns an unsigned file with the given number of fields besides the "Expires" field
+fn unsigned(fields: usize) -> String {
+    let mut text = String::from("# Generated for benchmarking\nExpires: 2999-01-01T00:00:00.000Z\n");
+    for i in 0..fields / 2 {
+        text.push_str(&format!("Contact: https://example.com/contact/{i}\n"));
+        text.push_str(&format!("X-Field-{i}: value {i}\n"));
+    }
+    text
+}
+
+/// Returns the unsigned file wrapped in a cleartext signature
+fn signed(fields: usize) -> String {
+    format!(
+        "-----BEGIN PGP SIGNED MESSAGE-----\nHash: SHA512\n\n{}-----BEGIN PGP SIGNATURE-----\n\n{SIGNATURE_DATA}-----END PGP SIGNATURE-----\n",
+        unsigned(fields)
+    )
+}
+
+/// Returns an unsigned file which only fails to parse at its last line
+fn malformed(fields: usize) -> String {
+    unsigned(fields) + "malformed line\n"
+}
+
+fn bench_corpus(c: &mut Criterion) {
+    let options = options();
+    let mut group = c.benchmark_group("corpus");
+
+    for name in ["valid_unsigned", "valid_signed", "gen_unsigned"] {
+        let files = category(name);
+        group.throughput(Throughput::Bytes(files.iter().map(|f| f.len() as u64).sum()));
+
+        group.bench_with_input(BenchmarkId::new("SecurityTxt", name), &files, |b, files| {
+            b.iter(|| {
+                for text in files {
+                    black_box(SecurityTxt::parse_with(black_box(text), &options).unwrap());
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("SecurityTxtRef", name), &files, |b, files| {
+            b.iter(|| {
+                for text in files {
+                    black_box(SecurityTxtRef::parse_with(black_box(text), &options).unwrap());
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("diagnose", name), &files, |b, files| {
+            b.iter(|| {
+                for text in files {
+                    black_box(SecurityTxt::diagnose(black_box(text), &options));
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_synthetic(c: &mut Criterion) {
+    let options = options();
+    let mut group = c.benchmark_group("synthetic");
+
+    for fields in [100, 1_000, 10_000] {
+        let inputs = [
+            ("unsigned", unsigned(fields)),
+            ("signed", signed(fields)),
+            ("malformed", malformed(fields)),
+        ];
+
+        for (kind, text) in inputs {
+            assert_eq!(SecurityTxt::parse_with(&text, &options).is_ok(), kind != "malformed");
+            group.throughput(Throughput::Bytes(text.len() as u64));
+
+            let id = BenchmarkId::new(format!("SecurityTxt/{kind}"), fields);
+            group.bench_with_input(id, &text, |b, text| {
+                b.iter(|| black_box(SecurityTxt::parse_with(black_box(text), &options)))
+            });
+            let id = BenchmarkId::new(format!("SecurityTxtRef/{kind}"), fields);
+            group.bench_with_input(id, &text, |b, text| {
+                b.iter(|| black_box(SecurityTxtRef::parse_with(black_box(text), &options)))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_corpus, bench_synthetic);
+criterion_main!(benches);
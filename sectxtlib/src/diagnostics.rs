@@ -0,0 +1,97 @@
+use super::parse_error::ParseError;
+use super::securitytxt::SecurityTxt;
+use super::securitytxt_partial::PartialSecurityTxt;
+use super::span::Span;
+
+/// The severity of a [`Finding`] or [`Lint`](crate::Lint)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The file is valid, but does not follow a recommendation which may not apply to every file
+    Info,
+
+    /// The file is valid, but the finding should be addressed
+    Warning,
+
+    /// The file is invalid according to [RFC 9116](https://www.rfc-editor.org/rfc/rfc9116)
+    Error,
+}
+
+/// A single problem found while parsing a security.txt file
+#[derive(Debug, PartialEq)]
+pub struct Finding {
+    /// How severe the problem is
+    pub severity: Severity,
+
+    /// The problem itself, which carries its location in the parsed file
+    pub error: ParseError,
+}
+
+impl Finding {
+    pub(crate) fn error(error: ParseError) -> Self {
+        Self {
+            severity: Severity::Error,
+            error,
+        }
+    }
+
+    pub(crate) fn warning(error: ParseError) -> Self {
+        Self {
+            severity: Severity::Warning,
+            error,
+        }
+    }
+
+    /// Returns the stable code identifying the problem
+    pub fn code(&self) -> &'static str {
+        self.error.code()
+    }
+
+    /// Returns the location of the problem in the parsed file, if it relates to a specific line
+    pub fn span(&self) -> Option<&Span> {
+        self.error.span()
+    }
+}
+
+/// The result of parsing a security.txt file in diagnostics mode
+///
+/// Unlike [`SecurityTxt::parse_with`], diagnostics mode does not stop at the first problem. Instead, it collects every
+/// problem found in the file and keeps all fields which could be parsed.
+#[derive(Debug, PartialEq)]
+pub struct Diagnostics {
+    /// All problems found in the file, in the order of their location, followed by those without a location
+    pub findings: Vec<Finding>,
+
+    /// All fields which could be parsed successfully
+    pub partial: PartialSecurityTxt,
+}
+
+impl Diagnostics {
+    /// Returns whether the file is valid, i.e., whether no finding has [`Severity::Error`]
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Returns all findings with [`Severity::Error`]
+    pub fn errors(&self) -> impl Iterator<Item = &Finding> {
+        self.findings.iter().filter(|f| f.severity == Severity::Error)
+    }
+
+    /// Returns all findings with [`Severity::Warning`]
+    pub fn warnings(&self) -> impl Iterator<Item = &Finding> {
+        self.findings.iter().filter(|f| f.severity == Severity::Warning)
+    }
+
+    /// Converts the diagnostics into a [`SecurityTxt`], failing with the first error if the file is not valid
+    pub fn into_result(self) -> Result<SecurityTxt, ParseError> {
+        first_error(self.findings)?;
+        self.partial.try_into()
+    }
+}
+
+/// Fails with the first finding which has [`Severity::Error`], if there is any
+pub(crate) fn first_error(findings: Vec<Finding>) -> Result<(), ParseError> {
+    match findings.into_iter().find(|f| f.severity == Severity::Error) {
+        Some(finding) => Err(finding.error),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,320 @@
+use super::lint::LintKind;
+use super::parse_error::ParseErrorKind;
+
+/// A long-form explanation of a stable code, similar to the error index of a compiler
+///
+/// Codes starting with `STXT0` identify a [`ParseError`](crate::ParseError), while codes starting with `STXT1` identify
+/// a [`Lint`](crate::Lint).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Explanation {
+    /// The stable code, e.g., "STXT001"
+    pub code: &'static str,
+
+    /// A one-line summary of the problem
+    pub summary: &'static str,
+
+    /// A description of the problem and why it matters
+    pub explanation: &'static str,
+
+    /// The section of the specification the problem relates to
+    pub reference: &'static str,
+
+    /// A link to the section of the specification
+    pub url: &'static str,
+
+    /// A suggestion on how to fix the problem
+    pub fix: &'static str,
+}
+
+impl Explanation {
+    /// Returns the explanation of the given code, ignoring its case
+    pub fn find(code: &str) -> Option<&'static Explanation> {
+        EXPLANATIONS.iter().find(|e| e.code.eq_ignore_ascii_case(code.trim()))
+    }
+
+    /// Returns the explanations of all codes known to this library, ordered by code
+    pub fn all() -> &'static [Explanation] {
+        EXPLANATIONS
+    }
+}
+
+impl ParseErrorKind {
+    /// Returns the stable code identifying this kind of error, e.g., "STXT001"
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Malformed => "STXT001",
+            Self::InvalidUri => "STXT002",
+            Self::InvalidDatetime => "STXT003",
+            Self::InvalidLanguageTag => "STXT004",
+            Self::IllegalField => "STXT005",
+            Self::ContactFieldMissing => "STXT006",
+            Self::ExpiresFieldMissing => "STXT007",
+            Self::ExpiresFieldExpired => "STXT008",
+            Self::ExpiresFieldExpiresSoon => "STXT009",
+            Self::ExpiresFieldMultiple => "STXT010",
+            Self::PreferredLanguagesFieldMultiple => "STXT011",
+            Self::InsecureHTTP => "STXT012",
+            Self::ByteOrderMark => "STXT013",
+            Self::BareCarriageReturn => "STXT014",
+            Self::MissingFinalLineEnding => "STXT015",
+            Self::TabAfterSeparator => "STXT016",
+            Self::InputTooLarge => "STXT017",
+            Self::InvalidEncoding => "STXT018",
+            Self::Io => "STXT019",
+            Self::MalformedSignedMessage => "STXT020",
+            Self::ReservedExtensionField => "STXT021",
+        }
+    }
+
+    /// Returns the long-form explanation of this kind of error
+    pub fn explanation(&self) -> &'static Explanation {
+        Explanation::find(self.code()).expect("every kind of error is explained")
+    }
+}
+
+impl LintKind {
+    /// Returns the long-form explanation of this kind of lint
+    pub fn explanation(&self) -> &'static Explanation {
+        Explanation::find(self.code()).expect("every kind of lint is explained")
+    }
+}
+
+const EXPLANATIONS: &[Explanation] = &[
+    Explanation {
+        code: "STXT001",
+        summary: "invalid syntax",
+        explanation:
+            "A line of the file is neither a field, a comment, nor an empty line. Fields consist of a name, a \
+            colon, and a value. Lines starting with \"#\" are comments. Parsers reject the whole file if a single line \
+            does not follow the grammar.",
+        reference: "RFC 9116, Section 4",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-4",
+        fix: "Remove the offending line, prefix it with \"#\" to turn it into a comment, or separate the field name \
+            from its value with a colon.",
+    },
+    Explanation {
+        code: "STXT002",
+        summary: "invalid URI",
+        explanation: "The value of a field which links to a resource is not a valid URI. This concerns the \
+            Acknowledgments, Canonical, Contact, CSAF, Encryption, Hiring, and Policy fields.",
+        reference: "RFC 9116, Section 2.5",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.5",
+        fix: "Use an absolute URI including its scheme, e.g., \"mailto:security@example.com\" or \
+            \"https://example.com/policy\", and percent-encode characters such as spaces.",
+    },
+    Explanation {
+        code: "STXT003",
+        summary: "invalid date format",
+        explanation: "The value of the Expires field is not a date and time in the Internet date/time format of RFC \
+            3339. Without a valid expiry, researchers cannot tell whether the file is still maintained.",
+        reference: "RFC 9116, Section 2.5.5",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.5.5",
+        fix: "Specify the date and time including a time zone, e.g., \"2030-01-01T00:00:00Z\".",
+    },
+    Explanation {
+        code: "STXT004",
+        summary: "invalid language tag",
+        explanation: "A language in the Preferred-Languages field is not a valid language tag according to RFC 5646.",
+        reference: "RFC 9116, Section 2.5.8",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.5.8",
+        fix: "Use language tags such as \"en\" or \"de-AT\", and separate multiple languages with commas.",
+    },
+    Explanation {
+        code: "STXT005",
+        summary: "field specified in an illegal way",
+        explanation: "The name or the value of a field contains characters which the grammar does not allow, such as \
+            line breaks or control characters. Such a field cannot be written to a file without changing its meaning.",
+        reference: "RFC 9116, Section 4",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-4",
+        fix: "Remove line breaks and control characters from the field, and do not use colons in field names.",
+    },
+    Explanation {
+        code: "STXT006",
+        summary: "contact field must be specified",
+        explanation: "The file does not contain a Contact field. The Contact field is the only way for researchers to \
+            find out where to report vulnerabilities, so at least one is required.",
+        reference: "RFC 9116, Section 2.5.3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.5.3",
+        fix: "Add a Contact field, e.g., \"Contact: mailto:security@example.com\".",
+    },
+    Explanation {
+        code: "STXT007",
+        summary: "expires field must be specified",
+        explanation: "The file does not contain an Expires field. The field is required so that researchers do not \
+            rely on stale information.",
+        reference: "RFC 9116, Section 2.5.5",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.5.5",
+        fix: "Add an Expires field less than a year in the future, e.g., \"Expires: 2030-01-01T00:00:00Z\".",
+    },
+    Explanation {
+        code: "STXT008",
+        summary: "expires field specifies time in the past",
+        explanation: "The file has expired, so its content should be considered stale. Researchers should not rely on \
+            the contacts or policies listed in an expired file.",
+        reference: "RFC 9116, Section 2.5.5",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.5.5",
+        fix: "Review the content of the file, and update the Expires field to a date in the future.",
+    },
+    Explanation {
+        code: "STXT009",
+        summary: "expires field specifies time in the near future",
+        explanation: "The file expires soon. Once it has expired, its content should be considered stale.",
+        reference: "RFC 9116, Section 2.5.5",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.5.5",
+        fix: "Review the content of the file, and update the Expires field before it expires.",
+    },
+    Explanation {
+        code: "STXT010",
+        summary: "expires field may only be specified once",
+        explanation: "The file contains more than one Expires field, so it is unclear when the file expires.",
+        reference: "RFC 9116, Section 2.5.5",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.5.5",
+        fix: "Remove all but one Expires field.",
+    },
+    Explanation {
+        code: "STXT011",
+        summary: "preferred languages field may only be specified once",
+        explanation: "The file contains more than one Preferred-Languages field.",
+        reference: "RFC 9116, Section 2.5.8",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.5.8",
+        fix: "Combine the fields into a single Preferred-Languages field listing all languages separated by commas.",
+    },
+    Explanation {
+        code: "STXT012",
+        summary: "links must use HTTPS",
+        explanation: "A field links to a web resource over insecure HTTP. Resources referenced by the file must be \
+            retrieved over HTTPS, so that attackers cannot tamper with them in transit.",
+        reference: "RFC 9116, Section 2.5",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.5",
+        fix: "Serve the resource over HTTPS, and change the scheme of the link from \"http\" to \"https\".",
+    },
+    Explanation {
+        code: "STXT013",
+        summary: "file starts with a byte order mark",
+        explanation: "The file starts with a UTF-8 byte order mark, which the grammar does not allow. Strict parsers \
+            reject the file, while lenient parsers ignore the mark.",
+        reference: "RFC 9116, Section 4",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-4",
+        fix: "Save the file as UTF-8 without a byte order mark.",
+    },
+    Explanation {
+        code: "STXT014",
+        summary: "line ends with a bare carriage return",
+        explanation: "A line ends with a carriage return that is not followed by a line feed. Lines must end with \
+            either CRLF or LF, so strict parsers reject the file.",
+        reference: "RFC 9116, Section 2.2",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.2",
+        fix: "Save the file with LF or CRLF line endings.",
+    },
+    Explanation {
+        code: "STXT015",
+        summary: "last line does not end with a line break",
+        explanation: "Every line must end with a line break, including the last one, so strict parsers reject the \
+            file.",
+        reference: "RFC 9116, Section 2.2",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.2",
+        fix: "Add a line break at the end of the file.",
+    },
+    Explanation {
+        code: "STXT016",
+        summary: "field separator is followed by a tab instead of a space",
+        explanation: "The colon after a field name is followed by a tab. Strict parsers, including this library in \
+            strict mode, only accept a single space.",
+        reference: "RFC 9116, Section 4",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-4",
+        fix: "Replace the tab after the colon with a single space.",
+    },
+    Explanation {
+        code: "STXT017",
+        summary: "input exceeds the maximum size",
+        explanation: "The input is larger than the configured maximum size, so it was not parsed. A security.txt file \
+            is typically a few hundred bytes, so a large input is likely not a security.txt file at all.",
+        reference: "RFC 9116, Section 3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-3",
+        fix: "Make sure the location serves the security.txt file itself, and remove unnecessary content from it.",
+    },
+    Explanation {
+        code: "STXT018",
+        summary: "invalid encoding",
+        explanation: "The input contains a byte sequence which is not valid UTF-8. The file must be encoded as UTF-8, \
+            so it cannot be parsed.",
+        reference: "RFC 9116, Section 3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-3",
+        fix: "Save the file as UTF-8 without a byte order mark.",
+    },
+    Explanation {
+        code: "STXT019",
+        summary: "failed to read input",
+        explanation: "The input could not be read, e.g., because the connection was closed or the file is not \
+            accessible.",
+        reference: "RFC 9116, Section 3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-3",
+        fix: "Make sure the file can be read, and try again.",
+    },
+    Explanation {
+        code: "STXT020",
+        summary: "invalid OpenPGP cleartext signature framework",
+        explanation: "The file starts like a signed message, but its header, its \"Hash\" armor headers, or its \
+            signature block do not follow the cleartext signature framework, so neither the signature nor the fields \
+            can be read.",
+        reference: "RFC 9116, Section 2.3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.3",
+        fix: "Sign the file again, e.g., with \"gpg --clearsign\", and do not edit the result afterwards.",
+    },
+    Explanation {
+        code: "STXT021",
+        summary: "standard field added as an extension",
+        explanation: "An extension field has the name of a field defined by the specification. Once written to a \
+            file, it would be read as that field, e.g., as a second Expires field or as a Contact field which was never \
+            validated.",
+        reference: "RFC 9116, Section 2.4",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.4",
+        fix: "Add the field using its dedicated method, or choose a different name for the extension.",
+    },
+    Explanation {
+        code: "STXT101",
+        summary: "expires field is more than a year in the future",
+        explanation: "It is recommended that the Expires field is less than a year in the future, so that the file is \
+            reviewed regularly and does not become stale.",
+        reference: "RFC 9116, Section 2.5.5",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.5.5",
+        fix: "Set the Expires field to a date less than a year in the future, and update it regularly.",
+    },
+    Explanation {
+        code: "STXT102",
+        summary: "canonical field should be specified",
+        explanation: "The file does not contain a Canonical field. The field lists where the file is meant to be \
+            served, which lets researchers detect files copied from elsewhere. It is required for signed files.",
+        reference: "RFC 9116, Section 2.5.2",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.5.2",
+        fix: "Add a Canonical field for every location the file is served from, e.g., \
+            \"Canonical: https://example.com/.well-known/security.txt\".",
+    },
+    Explanation {
+        code: "STXT103",
+        summary: "canonical fields do not include the URL the file was retrieved from",
+        explanation: "None of the Canonical fields lists the URL the file was retrieved from. This is a frequent sign \
+            of a file copied from another domain, whose contacts may not be responsible for this one.",
+        reference: "RFC 9116, Section 2.5.2",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.5.2",
+        fix: "Add a Canonical field with the URL the file is served from, or correct the existing ones.",
+    },
+    Explanation {
+        code: "STXT104",
+        summary: "contact field should use a mailto, tel, or https URI",
+        explanation: "A Contact field uses a URI scheme other than \"mailto\", \"tel\", or \"https\", which \
+            researchers may not be able to use.",
+        reference: "RFC 9116, Section 2.5.3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.5.3",
+        fix: "Link to an email address, a phone number, or a web page instead.",
+    },
+    Explanation {
+        code: "STXT105",
+        summary: "file should be digitally signed",
+        explanation: "The file is not signed with OpenPGP. A signature lets researchers verify that the file was \
+            published by the owner of the domain.",
+        reference: "RFC 9116, Section 2.3",
+        url: "https://www.rfc-editor.org/rfc/rfc9116#section-2.3",
+        fix: "Sign the file as an OpenPGP cleartext message, e.g., with \"sectxt sign\".",
+    },
+];
@@ -1,23 +1,95 @@
 use super::parse_error::ParseError;
+use super::securitytxt_ref::{ExpiresFieldRef, ExtensionFieldRef, PreferredLanguagesFieldRef, UriFieldRef};
+use super::span::Span;
 use chrono::{DateTime, Utc};
-use iri_string::types::IriString;
+use iri_string::types::{IriStr, IriString};
 use oxilangtag::{LanguageTag, LanguageTagParseError};
 use std::cmp::Ordering;
 use valuable::{Valuable, Value, Visit};
 
+/// Validates the value of a field with a link, which is borrowed from the input
+pub(crate) fn parse_uri<'a>(field: &str, value: &'a str) -> Result<&'a IriStr, ParseError> {
+    let value = value.trim();
+    let uri = IriStr::new(value).map_err(|err| ParseError::InvalidUri {
+        field: field.to_owned(),
+        value: value.to_owned(),
+        cause: err.to_string(),
+        span: None,
+    })?;
+
+    if uri.scheme_str() == "http" {
+        return Err(ParseError::InsecureHTTP {
+            field: field.to_owned(),
+            value: value.to_owned(),
+            span: None,
+        });
+    }
+
+    Ok(uri)
+}
+
+/// Validates the value of the "Expires" field
+pub(crate) fn parse_datetime(value: &str) -> Result<DateTime<Utc>, ParseError> {
+    let value = value.trim();
+
+    value
+        .parse()
+        .map_err(|err: chrono::ParseError| ParseError::InvalidDatetime {
+            field: "Expires".to_owned(),
+            value: value.to_owned(),
+            cause: err.to_string(),
+            span: None,
+        })
+}
+
+/// Validates the value of the "Preferred-Languages" field with the given language tag parser
+pub(crate) fn parse_languages<'a, T>(
+    value: &'a str,
+    parse: impl Fn(&'a str) -> Result<LanguageTag<T>, LanguageTagParseError>,
+) -> Result<Vec<LanguageTag<T>>, ParseError> {
+    value
+        .split(',')
+        .map(str::trim)
+        .map(|tag| {
+            parse(tag).map_err(|err| ParseError::InvalidLanguageTag {
+                field: "Preferred-Languages".to_owned(),
+                value: tag.to_owned(),
+                cause: err.to_string(),
+                span: None,
+            })
+        })
+        .collect()
+}
+
 macro_rules! IriStringImpl {
-    ($structname:ident) => {
+    ($structname:ident, $fieldname:literal) => {
         impl $structname {
+            #[cfg(any(test, feature = "serde"))]
             pub(crate) fn new(uri: &str) -> Result<Self, ParseError> {
-                let uri = uri.trim().parse::<IriString>()?;
+                let uri = parse_uri($fieldname, uri)?.to_owned();
+                let log_value = uri.as_str().to_string();
 
-                if uri.scheme_str() == "http" {
-                    return Err(ParseError::InsecureHTTP);
-                }
+                Ok(Self {
+                    uri,
+                    span: None,
+                    log_value,
+                })
+            }
+        }
 
-                let log_value = uri.as_str().to_string();
+        impl From<&UriFieldRef<'_>> for $structname {
+            fn from(field: &UriFieldRef<'_>) -> Self {
+                Self {
+                    uri: field.uri.clone().into_owned(),
+                    span: field.span.clone(),
+                    log_value: field.uri.as_str().to_string(),
+                }
+            }
+        }
 
-                Ok(Self { uri, log_value })
+        impl PartialEq for $structname {
+            fn eq(&self, other: &Self) -> bool {
+                self.uri == other.uri
             }
         }
 
@@ -28,79 +100,185 @@ macro_rules! IriStringImpl {
 
             fn visit(&self, _visit: &mut dyn Visit) {}
         }
+
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $structname {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                UriRepr {
+                    uri: self.uri.as_str(),
+                    span: self.span.clone(),
+                }
+                .serialize(serializer)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $structname {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let repr = UriRepr::<String>::deserialize(deserializer)?;
+                let mut field = Self::new(&repr.uri).map_err(serde::de::Error::custom)?;
+                field.span = repr.span;
+                Ok(field)
+            }
+        }
     };
 }
 
+// The serialized forms of the fields. Values are validated in the same way as when parsing a file.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct UriRepr<T> {
+    uri: T,
+    span: Option<Span>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ExpiresRepr {
+    datetime: DateTime<Utc>,
+    span: Option<Span>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PreferredLanguagesRepr<T> {
+    languages: Vec<T>,
+    span: Option<Span>,
+}
+
 /// An [Acknowledgments field](https://www.rfc-editor.org/rfc/rfc9116#name-acknowledgments) links to a page where security researchers are recognized
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct AcknowledgmentsField {
     /// The URI of the link according to [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986)
     pub uri: IriString,
 
+    /// The location of the field in the parsed file, if it was parsed
+    pub span: Option<Span>,
+
     log_value: String,
 }
-IriStringImpl!(AcknowledgmentsField);
+IriStringImpl!(AcknowledgmentsField, "Acknowledgments");
 
 /// A [Canonical field](https://www.rfc-editor.org/rfc/rfc9116#name-canonical) contains a canonical URI for the security.txt file
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct CanonicalField {
     /// The URI of the link according to [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986)
     pub uri: IriString,
 
+    /// The location of the field in the parsed file, if it was parsed
+    pub span: Option<Span>,
+
     log_value: String,
 }
-IriStringImpl!(CanonicalField);
+IriStringImpl!(CanonicalField, "Canonical");
 
 /// A [Contact field](https://www.rfc-editor.org/rfc/rfc9116#name-contact) contains contact information to use for reporting vulnerabilities
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct ContactField {
     /// The URI of the link according to [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986)
     pub uri: IriString,
 
+    /// The location of the field in the parsed file, if it was parsed
+    pub span: Option<Span>,
+
     log_value: String,
 }
-IriStringImpl!(ContactField);
+IriStringImpl!(ContactField, "Contact");
 
 /// A [CSAF field](https://docs.oasis-open.org/csaf/csaf/v2.0/os/csaf-v2.0-os.html#718-requirement-8-securitytxt) contains the canonical URI for the CSAF provider-metadata.json
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct CsafField {
     /// The URI of the link according to the [CSAF Standard](https://docs.oasis-open.org/csaf/csaf/v2.0/os/csaf-v2.0-os.html#718-requirement-8-securitytxt)
     pub uri: IriString,
 
+    /// The location of the field in the parsed file, if it was parsed
+    pub span: Option<Span>,
+
     log_value: String,
 }
-IriStringImpl!(CsafField);
+IriStringImpl!(CsafField, "CSAF");
 
 /// An [Encryption field](https://www.rfc-editor.org/rfc/rfc9116#name-encryption) links to a key to be used for encrypted communication
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct EncryptionField {
     /// The URI of the link according to [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986)
     pub uri: IriString,
 
+    /// The location of the field in the parsed file, if it was parsed
+    pub span: Option<Span>,
+
     log_value: String,
 }
-IriStringImpl!(EncryptionField);
+IriStringImpl!(EncryptionField, "Encryption");
 
 /// The [Expires field](https://www.rfc-editor.org/rfc/rfc9116#name-expires) represents the date and time after which the security.txt file is considered stale
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct ExpiresField {
     /// The date and time from which the security.txt file is considered stale
     pub datetime: DateTime<Utc>,
 
+    /// The location of the field in the parsed file, if it was parsed
+    pub span: Option<Span>,
+
     log_value: String,
 }
 
 impl ExpiresField {
-    pub(crate) fn new(datetime: &str, now: DateTime<Utc>) -> Result<Self, ParseError> {
-        let datetime: DateTime<Utc> = datetime.trim().parse()?;
+    #[cfg(test)]
+    pub(crate) fn new(datetime: &str) -> Result<Self, ParseError> {
+        let datetime = parse_datetime(datetime)?;
+        let log_value = datetime.to_rfc3339();
+
+        Ok(Self {
+            datetime,
+            span: None,
+            log_value,
+        })
+    }
 
-        if datetime < now {
-            return Err(ParseError::ExpiresFieldExpired);
+    /// Returns whether the field specifies a time before `now`
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.datetime < now
+    }
+}
+
+impl From<&ExpiresFieldRef> for ExpiresField {
+    fn from(field: &ExpiresFieldRef) -> Self {
+        Self {
+            datetime: field.datetime,
+            span: field.span.clone(),
+            log_value: field.datetime.to_rfc3339(),
         }
+    }
+}
 
-        let log_value = datetime.to_rfc3339();
+impl PartialEq for ExpiresField {
+    fn eq(&self, other: &Self) -> bool {
+        self.datetime == other.datetime
+    }
+}
 
-        Ok(Self { datetime, log_value })
+#[cfg(feature = "serde")]
+impl serde::Serialize for ExpiresField {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ExpiresRepr {
+            datetime: self.datetime,
+            span: self.span.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ExpiresField {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ExpiresRepr::deserialize(deserializer)?;
+
+        Ok(Self {
+            datetime: repr.datetime,
+            span: repr.span,
+            log_value: repr.datetime.to_rfc3339(),
+        })
     }
 }
 
@@ -120,49 +298,100 @@ impl Valuable for ExpiresField {
 }
 
 /// A [Hiring field](https://www.rfc-editor.org/rfc/rfc9116#name-hiring) links to the vendor's security-related job positions
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct HiringField {
     /// The URI of the link according to [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986)
     pub uri: IriString,
 
+    /// The location of the field in the parsed file, if it was parsed
+    pub span: Option<Span>,
+
     log_value: String,
 }
-IriStringImpl!(HiringField);
+IriStringImpl!(HiringField, "Hiring");
 
 /// A [Policy field](https://www.rfc-editor.org/rfc/rfc9116#name-policy) links to the security policy page
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct PolicyField {
     /// The URI of the link according to [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986)
     pub uri: IriString,
 
+    /// The location of the field in the parsed file, if it was parsed
+    pub span: Option<Span>,
+
     log_value: String,
 }
-IriStringImpl!(PolicyField);
+IriStringImpl!(PolicyField, "Policy");
 
 /// The [Preferred-Languages field](https://www.rfc-editor.org/rfc/rfc9116#name-preferred-languages) lists the preferred languages for security reports
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct PreferredLanguagesField {
     /// The set of preferred languages according to [RFC 5646](https://www.rfc-editor.org/rfc/rfc5646)
     pub languages: Vec<LanguageTag<String>>,
 
+    /// The location of the field in the parsed file, if it was parsed
+    pub span: Option<Span>,
+
     log_value: String,
 }
 
 impl PreferredLanguagesField {
+    #[cfg(any(test, feature = "serde"))]
     pub(crate) fn new(languages: &str) -> Result<Self, ParseError> {
-        let languages = languages
-            .split(',')
-            .map(str::trim)
-            .map(LanguageTag::parse_and_normalize)
-            .collect::<Result<Vec<LanguageTag<String>>, LanguageTagParseError>>()?;
-
-        if languages.is_empty() {
-            return Err(ParseError::IllegalField);
-        }
+        let languages = parse_languages(languages, LanguageTag::parse_and_normalize)?;
+        let log_value = languages.join(", ");
+
+        Ok(Self {
+            languages,
+            span: None,
+            log_value,
+        })
+    }
+}
 
+impl From<&PreferredLanguagesFieldRef<'_>> for PreferredLanguagesField {
+    fn from(field: &PreferredLanguagesFieldRef<'_>) -> Self {
+        let languages: Vec<LanguageTag<String>> = field
+            .languages
+            .iter()
+            .map(|tag| {
+                LanguageTag::parse_and_normalize(tag.as_str()).expect("language tags are validated when parsing")
+            })
+            .collect();
         let log_value = languages.join(", ");
 
-        Ok(Self { languages, log_value })
+        Self {
+            languages,
+            span: field.span.clone(),
+            log_value,
+        }
+    }
+}
+
+impl PartialEq for PreferredLanguagesField {
+    fn eq(&self, other: &Self) -> bool {
+        self.languages == other.languages
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PreferredLanguagesField {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PreferredLanguagesRepr {
+            languages: self.languages.iter().map(LanguageTag::as_str).collect(),
+            span: self.span.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PreferredLanguagesField {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = PreferredLanguagesRepr::<String>::deserialize(deserializer)?;
+        let mut field = Self::new(&repr.languages.join(",")).map_err(serde::de::Error::custom)?;
+        field.span = repr.span;
+        Ok(field)
     }
 }
 
@@ -177,16 +406,40 @@ impl Valuable for PreferredLanguagesField {
 /// The "Extension" field acts as a catch-all for any fields not explicitly supported by this library
 ///
 /// This feature accommodates [section 2.4 on Extensibility](https://www.rfc-editor.org/rfc/rfc9116#name-extensibility) in the specification.
-#[derive(Debug, PartialEq, Valuable)]
+#[derive(Debug, Valuable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtensionField {
     /// Name of the extension field
     pub name: String,
     /// Value of the extension field
     pub value: String,
+    /// The location of the field in the parsed file, if it was parsed
+    pub span: Option<Span>,
 }
 
+#[cfg(test)]
 impl ExtensionField {
     pub(crate) fn new(name: String, value: String) -> Result<Self, ParseError> {
-        Ok(Self { name, value })
+        Ok(Self {
+            name,
+            value,
+            span: None,
+        })
+    }
+}
+
+impl From<&ExtensionFieldRef<'_>> for ExtensionField {
+    fn from(field: &ExtensionFieldRef<'_>) -> Self {
+        Self {
+            name: field.name.to_lowercase(),
+            value: field.value.to_string(),
+            span: field.span.clone(),
+        }
+    }
+}
+
+impl PartialEq for ExtensionField {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.value == other.value
     }
 }
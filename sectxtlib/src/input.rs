@@ -0,0 +1,96 @@
+use super::parse_error::ParseError;
+use super::securitytxt_options::SecurityTxtOptions;
+use super::span::{SourceMap, Span};
+use std::borrow::Cow;
+use std::io::Read;
+use std::ops::Range;
+
+/// The byte order marks of UTF-16, which is accepted in lenient mode
+const UTF16LE_BOM: [u8; 2] = [0xFF, 0xFE];
+const UTF16BE_BOM: [u8; 2] = [0xFE, 0xFF];
+
+/// Reads the input, but not beyond the maximum size
+pub(crate) fn read(mut reader: impl Read, options: &SecurityTxtOptions) -> Result<Vec<u8>, ParseError> {
+    let mut bytes = vec![];
+
+    // One byte more than the limit is read, so that exceeding the limit can be detected.
+    let result = match options.max_size {
+        Some(limit) => reader.take(limit as u64 + 1).read_to_end(&mut bytes),
+        None => reader.read_to_end(&mut bytes),
+    };
+    result.map_err(|err| ParseError::Io { cause: err.to_string() })?;
+
+    Ok(bytes)
+}
+
+/// Decodes the input as UTF-8, or as UTF-16 if it starts with a corresponding byte order mark in lenient mode
+///
+/// A UTF-8 byte order mark is left in place, as it is handled by the parsers depending on the `strict` option.
+pub(crate) fn decode<'a>(bytes: &'a [u8], options: &SecurityTxtOptions) -> Result<Cow<'a, str>, ParseError> {
+    if options.max_size.is_some_and(|limit| bytes.len() > limit) {
+        return Err(ParseError::InputTooLarge);
+    }
+
+    if !options.strict {
+        if let Some(rest) = bytes.strip_prefix(&UTF16LE_BOM) {
+            return decode_utf16(rest, u16::from_le_bytes).map(Cow::Owned);
+        }
+        if let Some(rest) = bytes.strip_prefix(&UTF16BE_BOM) {
+            return decode_utf16(rest, u16::from_be_bytes).map(Cow::Owned);
+        }
+    }
+
+    std::str::from_utf8(bytes).map(Cow::Borrowed).map_err(|err| {
+        let start = err.valid_up_to();
+        let end = start + err.error_len().unwrap_or(bytes.len() - start);
+        // The bytes up to the invalid sequence are valid UTF-8.
+        let valid = std::str::from_utf8(&bytes[..start]).unwrap_or_default();
+
+        ParseError::InvalidEncoding {
+            span: locate(valid, start..end),
+            cause: err.to_string(),
+        }
+    })
+}
+
+/// Decodes UTF-16 code units following the byte order mark
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Result<String, ParseError> {
+    let offset = UTF16LE_BOM.len();
+    let units = bytes.chunks_exact(2).map(|unit| from_bytes([unit[0], unit[1]]));
+    let mut text = String::with_capacity(bytes.len() / 2);
+
+    for c in char::decode_utf16(units) {
+        match c {
+            Ok(c) => text.push(c),
+            Err(err) => {
+                // All code units before the unpaired surrogate were decoded into the text.
+                let units: usize = text.chars().map(char::len_utf16).sum();
+                let start = offset + 2 * units;
+                return Err(ParseError::InvalidEncoding {
+                    span: locate(&text, start..start + 2),
+                    cause: err.to_string(),
+                });
+            }
+        }
+    }
+
+    if !bytes.len().is_multiple_of(2) {
+        let start = offset + bytes.len() - 1;
+        return Err(ParseError::InvalidEncoding {
+            span: locate(&text, start..start + 1),
+            cause: "incomplete UTF-16 code unit at the end of the input".to_owned(),
+        });
+    }
+
+    Ok(text)
+}
+
+/// Returns the span of the given byte range of the input, of which `valid` is the decoded part before the range
+fn locate(valid: &str, range: Range<usize>) -> Span {
+    let end = valid.len();
+    Span {
+        start: range.start,
+        end: range.end,
+        ..SourceMap::new(valid).span(end..end)
+    }
+}
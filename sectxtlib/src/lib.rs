@@ -1,26 +1,61 @@
+mod diagnostics;
+mod explanation;
 mod fields;
+mod input;
+mod lint;
 mod parse_error;
 mod parsers;
+mod pgparmor;
 mod pgpcleartextmessage;
+mod pgppacket;
+#[cfg(feature = "openpgp")]
+mod pgpsign;
+#[cfg(feature = "openpgp")]
+mod pgpverify;
 mod raw_field;
+mod render;
 mod securitytxt;
+mod securitytxt_builder;
 mod securitytxt_options;
+mod securitytxt_partial;
+mod securitytxt_ref;
+mod signature_error;
+mod signature_info;
+mod span;
 
+pub use diagnostics::{Diagnostics, Finding, Severity};
+pub use explanation::Explanation;
 pub use fields::{
-    AcknowledgmentsField, CanonicalField, ContactField, EncryptionField, ExpiresField, ExtensionField, HiringField,
-    PolicyField, PreferredLanguagesField,
+    AcknowledgmentsField, CanonicalField, ContactField, CsafField, EncryptionField, ExpiresField, ExtensionField,
+    HiringField, PolicyField, PreferredLanguagesField,
 };
-pub use parse_error::ParseError;
+pub use lint::{Lint, LintKind, LintOptions};
+pub use parse_error::{ParseError, ParseErrorKind};
+pub use pgppacket::{HashAlgorithm, PublicKeyAlgorithm};
+#[cfg(feature = "openpgp")]
+pub use pgpsign::SigningKey;
+#[cfg(feature = "openpgp")]
+pub use pgpverify::{Keyring, SignatureValidity, SignatureVerification};
+pub use render::RenderOptions;
 pub use securitytxt::SecurityTxt;
-pub use securitytxt_options::SecurityTxtOptions;
+pub use securitytxt_builder::SecurityTxtBuilder;
+pub use securitytxt_options::{ExpiryPolicy, SecurityTxtOptions};
+pub use securitytxt_partial::PartialSecurityTxt;
+pub use securitytxt_ref::{
+    ExpiresFieldRef, ExtensionFieldRef, PreferredLanguagesFieldRef, SecurityTxtRef, UriFieldRef,
+};
+pub use signature_error::SignatureError;
+pub use signature_info::SignatureInfo;
+pub use span::Span;
 
 #[cfg(test)]
 mod tests {
-    use crate::fields::CsafField;
-
     use super::*;
     use chrono::{DateTime, Datelike, Duration, SecondsFormat, TimeZone, Utc};
+    use proptest::prelude::*;
+    use std::borrow::Cow;
     use std::{fs, path::PathBuf};
+    use valuable::{Valuable, Value};
 
     const URL: &str = "https://securitytxt.org/";
     const INSECURE_URL: &str = "http://securitytxt.org/";
@@ -34,13 +69,13 @@ mod tests {
     }
 
     fn expires_dt(expires: &str) -> ExpiresField {
-        ExpiresField::new(expires, some_datetime()).unwrap()
+        ExpiresField::new(expires).unwrap()
     }
 
     fn get_parse_options() -> SecurityTxtOptions {
         SecurityTxtOptions {
             now: some_datetime(),
-            strict: true,
+            ..Default::default()
         }
     }
 
@@ -65,6 +100,7 @@ mod tests {
             hiring: vec![],
             policy: vec![],
             preferred_languages: None,
+            signature: None,
         };
 
         assert_eq!(file.parse(), Ok(sec));
@@ -85,6 +121,7 @@ mod tests {
             hiring: vec![],
             policy: vec![],
             preferred_languages: None,
+            signature: None,
         };
 
         assert_eq!(file.parse(), Ok(sec));
@@ -105,6 +142,7 @@ mod tests {
             hiring: vec![],
             policy: vec![],
             preferred_languages: None,
+            signature: None,
         };
 
         assert_eq!(file.parse(), Ok(sec));
@@ -125,6 +163,7 @@ mod tests {
             hiring: vec![],
             policy: vec![],
             preferred_languages: None,
+            signature: None,
         };
 
         assert_eq!(file.parse(), Ok(sec));
@@ -145,6 +184,7 @@ mod tests {
             hiring: vec![],
             policy: vec![],
             preferred_languages: None,
+            signature: None,
         };
 
         assert_eq!(file.parse(), Ok(sec));
@@ -169,8 +209,20 @@ mod tests {
     fn test_trailing_content() {
         let expires = future_expires_str();
         let file = format!("Contact: {URL}\nExpires: {expires}\nfoo");
+        let err = file.parse::<SecurityTxt>().unwrap_err();
+        let start = file.len() - 3;
 
-        assert_eq!(file.parse::<SecurityTxt>(), Err(ParseError::Malformed));
+        assert_eq!(err.kind(), ParseErrorKind::Malformed);
+        assert_eq!(err.value(), Some("foo"));
+        assert_eq!(
+            err.span(),
+            Some(&Span {
+                line: 3,
+                column: 1,
+                start,
+                end: start + 3,
+            })
+        );
     }
 
     #[test]
@@ -188,6 +240,7 @@ mod tests {
             hiring: vec![],
             policy: vec![],
             preferred_languages: Some(PreferredLanguagesField::new("en, fr").unwrap()),
+            signature: None,
         };
 
         assert_eq!(file.parse::<SecurityTxt>(), Ok(sec));
@@ -198,9 +251,17 @@ mod tests {
         let expires = future_expires_str();
         let file = format!("Contact: {URL}\nExpires: {expires}\nPreferred-Languages: en\nPreferred-Languages: de\n");
 
+        let start = file.rfind("Preferred-Languages").unwrap();
+        let span = Span {
+            line: 4,
+            column: 1,
+            start,
+            end: file.len() - 1,
+        };
+
         assert_eq!(
             file.parse::<SecurityTxt>(),
-            Err(ParseError::PreferredLanguagesFieldMultiple)
+            Err(ParseError::PreferredLanguagesFieldMultiple { span: Some(span) })
         );
     }
 
@@ -209,7 +270,18 @@ mod tests {
         let expires = future_expires_str();
         let file = format!("Contact: {URL}\nExpires: {expires}\nExpires: {expires}\n");
 
-        assert_eq!(file.parse::<SecurityTxt>(), Err(ParseError::ExpiresFieldMultiple));
+        let start = file.rfind("Expires").unwrap();
+        let span = Span {
+            line: 3,
+            column: 1,
+            start,
+            end: file.len() - 1,
+        };
+
+        assert_eq!(
+            file.parse::<SecurityTxt>(),
+            Err(ParseError::ExpiresFieldMultiple { span: Some(span) })
+        );
     }
 
     #[test]
@@ -217,7 +289,19 @@ mod tests {
         let expires = future_expires_str();
         let file = format!("Contact: {INSECURE_URL}\nExpires: {expires}\n");
 
-        assert_eq!(file.parse::<SecurityTxt>(), Err(ParseError::InsecureHTTP));
+        assert_eq!(
+            file.parse::<SecurityTxt>(),
+            Err(ParseError::InsecureHTTP {
+                field: "Contact".to_owned(),
+                value: INSECURE_URL.to_owned(),
+                span: Some(Span {
+                    line: 1,
+                    column: 1,
+                    start: 0,
+                    end: file.find('\n').unwrap(),
+                }),
+            })
+        );
     }
 
     #[test]
@@ -248,9 +332,20 @@ abcdefABCDEF/+==\r
             hiring: vec![],
             policy: vec![],
             preferred_languages: None,
+            signature: None,
         };
 
-        assert_eq!(file.parse(), Ok(sec));
+        let mut parsed: SecurityTxt = file.parse().unwrap();
+        let signature = parsed.signature.take().unwrap();
+        assert_eq!(parsed, sec);
+        assert_eq!(signature.hash_algorithms, vec!["SHA256"]);
+        assert_eq!(
+            signature.armor_headers,
+            vec![("Version".to_owned(), "GnuPG v2.2".to_owned())]
+        );
+        assert_eq!(signature.armored, "abcdefABCDEF/+==\r\n");
+        assert!(signature.data.is_err());
+        assert_eq!(signature.issuer_key_id, None);
     }
 
     fn _test_category(category: &str) {
@@ -260,7 +355,7 @@ abcdefABCDEF/+==\r
             let buf = fs::read_to_string(path.unwrap().path()).unwrap();
             let parse_options = get_parse_options();
             let txt = SecurityTxt::parse_with(&buf, &parse_options);
-            assert_eq!(txt.is_ok(), true);
+            assert!(txt.is_ok());
         }
     }
 
@@ -279,6 +374,116 @@ abcdefABCDEF/+==\r
         _test_category("gen_unsigned")
     }
 
+    fn _test_category_ref(category: &str) {
+        let paths = get_tests_dir(category).read_dir().unwrap();
+
+        for path in paths {
+            let buf = fs::read_to_string(path.unwrap().path()).unwrap();
+            let parse_options = get_parse_options();
+            let txt = SecurityTxtRef::parse_with(&buf, &parse_options).unwrap();
+
+            let input = buf.as_bytes().as_ptr_range();
+            assert!(txt.contact.iter().all(|c| input.contains(&c.uri.as_str().as_ptr())));
+            assert!(txt.extension.iter().all(|e| input.contains(&e.name.as_ptr())));
+            assert_eq!(Ok(txt.into_owned()), SecurityTxt::parse_with(&buf, &parse_options));
+        }
+    }
+
+    #[test]
+    fn test_ref_category_valid_unsigned() {
+        _test_category_ref("valid_unsigned")
+    }
+
+    #[test]
+    fn test_ref_category_valid_signed() {
+        _test_category_ref("valid_signed")
+    }
+
+    #[test]
+    fn test_ref_category_gen_unsigned() {
+        _test_category_ref("gen_unsigned")
+    }
+
+    #[test]
+    fn test_ref_errors() {
+        let expires = future_expires_str();
+        let files = [
+            format!("Expires: {expires}\n"),
+            format!("Contact: {URL}\n"),
+            format!("Contact: {INSECURE_URL}\nExpires: {expires}\n"),
+            format!("Contact: {URL}\nExpires: 2000-01-01T00:00:00Z\n"),
+            format!("Contact: {URL}\nExpires: tomorrow\n"),
+            format!("Contact: {URL}\nExpires: {expires}\nExpires: {expires}\n"),
+            format!("Contact: {URL}\nExpires: {expires}\nPreferred-Languages: en, abcdefghi\n"),
+            format!("Contact: {URL}\nExpires: {expires}\nfoo"),
+        ];
+
+        for file in files {
+            let owned = SecurityTxt::parse_with(&file, &get_parse_options());
+            assert!(owned.is_err());
+            assert_eq!(
+                SecurityTxtRef::parse_with(&file, &get_parse_options()).map(SecurityTxtRef::into_owned),
+                owned
+            );
+        }
+
+        let file = format!("CONTACT: {URL}\nExpires: {expires}\nPreferred-Languages: EN\nX-Foo: bar\n");
+        let txt = SecurityTxtRef::parse_with(&file, &get_parse_options()).unwrap();
+        assert_eq!(txt.preferred_languages.as_ref().unwrap().languages[0].as_str(), "EN");
+        assert_eq!(txt.extension[0].name, "X-Foo");
+        assert_eq!(
+            Ok(txt.into_owned()),
+            SecurityTxt::parse_with(&file, &get_parse_options())
+        );
+    }
+
+    #[test]
+    fn test_ref_dash_escaped_folded_value() {
+        let expires = future_expires_str();
+        let file = format!(
+            "-----BEGIN PGP SIGNED MESSAGE-----\r
+Hash: SHA256\r
+\r
+Contact: {URL}\r
+Expires: {expires}\r
+X-Folded: first\r
+-  second\r
+X-Plain: value\r
+-----BEGIN PGP SIGNATURE-----\r
+\r
+abcdefABCDEF/+==\r
+-----END PGP SIGNATURE-----\r
+"
+        );
+        let txt = SecurityTxtRef::parse_with(&file, &get_parse_options()).unwrap();
+
+        assert_eq!(txt.extension[0].value, "first\r\n second");
+        assert!(matches!(txt.extension[0].value, Cow::Owned(_)));
+        assert_eq!(txt.extension[1].value, "value");
+        assert!(matches!(txt.extension[1].value, Cow::Borrowed(_)));
+        assert!(matches!(txt.contact[0].uri, Cow::Borrowed(_)));
+        assert_eq!(
+            Ok(txt.into_owned()),
+            SecurityTxt::parse_with(&file, &get_parse_options())
+        );
+    }
+
+    #[test]
+    fn test_ref_expires_log_value() {
+        let file = format!("Contact: {URL}\nExpires: 2999-01-01T01:00:00+01:00\n");
+        let borrowed = SecurityTxtRef::parse_with(&file, &get_parse_options()).unwrap();
+        let owned = SecurityTxt::parse_with(&file, &get_parse_options()).unwrap();
+
+        assert!(matches!(
+            borrowed.expires.as_value(),
+            Value::String("2999-01-01T00:00:00+00:00")
+        ));
+        assert!(matches!(
+            owned.expires.as_value(),
+            Value::String("2999-01-01T00:00:00+00:00")
+        ));
+    }
+
     #[test]
     fn test_expires_non_z_time() {
         let next_year = Utc::now().year() + 1;
@@ -309,11 +514,12 @@ abcdefABCDEF/+==\r
                 contact: vec![ContactField::new(URL).unwrap()],
                 csaf: vec![],
                 encryption: vec![],
-                expires: ExpiresField::new(expires_str, some_datetime()).unwrap(),
+                expires: ExpiresField::new(expires_str).unwrap(),
                 extension: vec![],
                 hiring: vec![],
                 policy: vec![],
                 preferred_languages: None,
+                signature: None,
             };
 
             let parsed: SecurityTxt = file.parse().unwrap();
@@ -326,4 +532,1066 @@ abcdefABCDEF/+==\r
             );
         }
     }
+
+    #[test]
+    fn test_diagnose_collects_all_errors() {
+        let file = format!(
+            "Contact: {INSECURE_URL}\nContact: {URL}\nExpires: 2000-01-01T00:00:00Z\nPreferred-Languages: en\nPreferred-Languages: de\n"
+        );
+        let diagnostics = SecurityTxt::diagnose(&file, &get_parse_options());
+        let errors: Vec<ParseErrorKind> = diagnostics.errors().map(|f| f.error.kind()).collect();
+
+        assert_eq!(
+            errors,
+            vec![
+                ParseErrorKind::InsecureHTTP,
+                ParseErrorKind::ExpiresFieldExpired,
+                ParseErrorKind::PreferredLanguagesFieldMultiple,
+            ]
+        );
+        assert!(!diagnostics.is_valid());
+        assert_eq!(diagnostics.partial.contact, vec![ContactField::new(URL).unwrap()]);
+        assert_eq!(
+            diagnostics.partial.preferred_languages,
+            Some(PreferredLanguagesField::new("en").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_diagnose_malformed_lines() {
+        let expires = future_expires_str();
+        let file = format!("Contact: {URL}\ninvalid\nExpires: {expires}\nfoo");
+        let diagnostics = SecurityTxt::diagnose(&file, &get_parse_options());
+        let errors: Vec<(ParseErrorKind, Option<&str>)> = diagnostics
+            .errors()
+            .map(|f| (f.error.kind(), f.error.value()))
+            .collect();
+
+        assert_eq!(
+            errors,
+            vec![
+                (ParseErrorKind::Malformed, Some("invalid")),
+                (ParseErrorKind::Malformed, Some("foo"))
+            ]
+        );
+        assert_eq!(diagnostics.partial.contact, vec![ContactField::new(URL).unwrap()]);
+        assert_eq!(diagnostics.partial.expires, Some(expires_dt(&expires)));
+        assert_eq!(
+            diagnostics.into_result().map_err(|e| e.kind()),
+            Err(ParseErrorKind::Malformed)
+        );
+    }
+
+    #[test]
+    fn test_diagnose_malformed_signed() {
+        let file = read_openpgp_resource("signed_ed25519.stxt")
+            .replace("-----BEGIN PGP SIGNATURE-----", "-----BEGIN PGP SIGNATURE----");
+        let diagnostics = SecurityTxt::diagnose(&file, &get_parse_options());
+        let start = file.find("-----BEGIN PGP SIGNATURE").unwrap();
+        let line = file[..start].lines().count() + 1;
+
+        assert_eq!(diagnostics.findings.len(), 1);
+        let finding = &diagnostics.findings[0];
+        assert_eq!(finding.error.kind(), ParseErrorKind::MalformedSignedMessage);
+        assert_eq!(finding.error.value(), Some("-----BEGIN PGP SIGNATURE----"));
+        assert_eq!(finding.span().map(|s| (s.line, s.start)), Some((line, start)));
+        assert_eq!(
+            diagnostics.into_result(),
+            SecurityTxt::parse_with(&file, &get_parse_options())
+        );
+    }
+
+    #[test]
+    fn test_expiry_policy() {
+        let file = format!("Contact: {URL}\nContact: mailto:security@example.com\nExpires: 2000-01-01T00:00:00Z\n");
+        let test_vector = [
+            (ExpiryPolicy::Reject, Some(Severity::Error)),
+            (ExpiryPolicy::Warn, Some(Severity::Warning)),
+            (ExpiryPolicy::Ignore, None),
+        ];
+
+        for (expiry, severity) in test_vector {
+            let options = SecurityTxtOptions {
+                expiry,
+                ..get_parse_options()
+            };
+            let diagnostics = SecurityTxt::diagnose(&file, &options);
+            let findings: Vec<(Severity, &ParseError, Option<usize>)> = diagnostics
+                .findings
+                .iter()
+                .map(|f| (f.severity, &f.error, f.span().map(|s| s.line)))
+                .collect();
+
+            let start = file.find("Expires").unwrap();
+            let expected_error = ParseError::ExpiresFieldExpired {
+                value: "2000-01-01T00:00:00+00:00".to_owned(),
+                span: Some(Span {
+                    line: 3,
+                    column: 1,
+                    start,
+                    end: file.len() - 1,
+                }),
+            };
+            let expected: Vec<(Severity, &ParseError, Option<usize>)> = severity
+                .map(|severity| (severity, &expected_error, Some(3)))
+                .into_iter()
+                .collect();
+            assert_eq!(findings, expected);
+            assert_eq!(diagnostics.partial.contact.len(), 2);
+
+            let txt = SecurityTxt::parse_with(&file, &options);
+            match expiry {
+                ExpiryPolicy::Reject => {
+                    assert_eq!(txt.map_err(|err| err.kind()), Err(ParseErrorKind::ExpiresFieldExpired))
+                }
+                _ => {
+                    let txt = txt.unwrap();
+                    assert_eq!(txt.contact.len(), 2);
+                    assert!(txt.expires.is_expired(options.now));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_expires_soon() {
+        let expires = (some_datetime() + Duration::days(10)).to_rfc3339_opts(SecondsFormat::Millis, true);
+        let file = format!("Contact: {URL}\nExpires: {expires}\n");
+
+        let options = SecurityTxtOptions {
+            expires_soon: Some(Duration::days(30)),
+            ..get_parse_options()
+        };
+        let diagnostics = SecurityTxt::diagnose(&file, &options);
+        let warnings: Vec<ParseErrorKind> = diagnostics.warnings().map(|f| f.error.kind()).collect();
+        assert_eq!(warnings, vec![ParseErrorKind::ExpiresFieldExpiresSoon]);
+        assert!(diagnostics.is_valid());
+        assert!(SecurityTxt::parse_with(&file, &options).is_ok());
+
+        let options = SecurityTxtOptions {
+            expires_soon: Some(Duration::days(5)),
+            ..get_parse_options()
+        };
+        assert_eq!(SecurityTxt::diagnose(&file, &options).findings, vec![]);
+    }
+
+    #[test]
+    fn test_lenient() {
+        let expires = future_expires_str();
+        let file = format!("\u{FEFF}Contact:\t{URL}\rExpires: {expires}");
+
+        assert_eq!(
+            file.parse::<SecurityTxt>().map_err(|e| e.kind()),
+            Err(ParseErrorKind::Malformed)
+        );
+
+        let options = SecurityTxtOptions {
+            strict: false,
+            ..get_parse_options()
+        };
+        let txt = SecurityTxt::parse_with(&file, &options).unwrap();
+        assert_eq!(txt.contact, vec![ContactField::new(URL).unwrap()]);
+        assert_eq!(txt.contact[0].span.as_ref().map(|s| (s.line, s.column)), Some((1, 2)));
+
+        let diagnostics = SecurityTxt::diagnose(&file, &options);
+        let warnings: Vec<(ParseErrorKind, usize, usize)> = diagnostics
+            .warnings()
+            .map(|f| {
+                let span = f.span().unwrap();
+                (f.error.kind(), span.line, span.column)
+            })
+            .collect();
+
+        assert_eq!(
+            warnings,
+            vec![
+                (ParseErrorKind::ByteOrderMark, 1, 1),
+                (ParseErrorKind::TabAfterSeparator, 1, 10),
+                (ParseErrorKind::BareCarriageReturn, 1, 35),
+                (ParseErrorKind::MissingFinalLineEnding, 2, 34),
+            ]
+        );
+        assert_eq!(diagnostics.into_result(), Ok(txt));
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        let expires = future_expires_str();
+        let file = format!("Contact: {URL}\nExpires: {expires}\n");
+        let txt = SecurityTxt::parse_with(&file, &get_parse_options()).unwrap();
+        assert_eq!(
+            SecurityTxt::parse_bytes_with(file.as_bytes(), &get_parse_options()),
+            Ok(txt)
+        );
+
+        let mut bytes = file.clone().into_bytes();
+        bytes.splice(URL.len() + 10..URL.len() + 10, [b'#', 0xC3, 0x28]);
+        let err = SecurityTxt::parse_bytes_with(&bytes, &get_parse_options()).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidEncoding);
+        assert_eq!(
+            err.span(),
+            Some(&Span {
+                line: 2,
+                column: 2,
+                start: URL.len() + 11,
+                end: URL.len() + 12,
+            })
+        );
+
+        let options = SecurityTxtOptions {
+            max_size: Some(file.len() - 1),
+            ..get_parse_options()
+        };
+        assert_eq!(
+            SecurityTxt::parse_bytes_with(file.as_bytes(), &options),
+            Err(ParseError::InputTooLarge)
+        );
+    }
+
+    #[test]
+    fn test_parse_bytes_bom() {
+        let expires = future_expires_str();
+        let file = format!("Contact: {URL}\nExpires: {expires}\n");
+        let lenient = SecurityTxtOptions {
+            strict: false,
+            ..get_parse_options()
+        };
+        let txt = SecurityTxt::parse_with(&file, &get_parse_options()).unwrap();
+
+        let utf8 = format!("\u{FEFF}{file}").into_bytes();
+        let utf16le: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(file.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        let utf16be: Vec<u8> = [0xFE, 0xFF]
+            .into_iter()
+            .chain(file.encode_utf16().flat_map(u16::to_be_bytes))
+            .collect();
+
+        for bytes in [&utf8, &utf16le, &utf16be] {
+            assert!(SecurityTxt::parse_bytes_with(bytes, &get_parse_options()).is_err());
+            let parsed = SecurityTxt::parse_bytes_with(bytes, &lenient).unwrap();
+            assert_eq!(parsed.contact, txt.contact);
+            assert_eq!(parsed.expires, txt.expires);
+        }
+
+        assert_eq!(
+            SecurityTxt::decode_bytes(&utf8, &lenient).unwrap(),
+            format!("\u{FEFF}{file}")
+        );
+        assert_eq!(SecurityTxt::decode_bytes(&utf16be, &lenient).unwrap(), file);
+
+        let err = SecurityTxt::parse_bytes_with(&utf16le[..utf16le.len() - 1], &lenient).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidEncoding);
+        let span = err.span().unwrap();
+        assert_eq!((span.line, span.start), (2, utf16le.len() - 2));
+    }
+
+    #[test]
+    fn test_parse_reader() {
+        struct FailingReader;
+
+        impl std::io::Read for FailingReader {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("connection reset"))
+            }
+        }
+
+        let expires = future_expires_str();
+        let file = format!("Contact: {URL}\nExpires: {expires}\n");
+        let txt = SecurityTxt::parse_with(&file, &get_parse_options()).unwrap();
+        assert_eq!(
+            SecurityTxt::parse_reader_with(file.as_bytes(), &get_parse_options()),
+            Ok(txt)
+        );
+
+        let options = SecurityTxtOptions {
+            max_size: Some(16),
+            ..get_parse_options()
+        };
+        let endless = std::io::repeat(b'#');
+        assert_eq!(
+            SecurityTxt::parse_reader_with(endless, &options),
+            Err(ParseError::InputTooLarge)
+        );
+        let bytes = SecurityTxt::read_bytes(std::io::repeat(b'#'), &options).unwrap();
+        assert_eq!(bytes.len(), 17);
+        assert_eq!(
+            SecurityTxt::decode_bytes(&bytes, &options),
+            Err(ParseError::InputTooLarge)
+        );
+
+        let err = SecurityTxt::parse_reader(FailingReader).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::Io);
+        assert_eq!(err.to_string(), "failed to read input: connection reset");
+    }
+
+    #[test]
+    fn test_lenient_signed() {
+        let file = format!("\u{FEFF}{}", read_openpgp_resource("signed_ed25519.stxt"));
+        let options = SecurityTxtOptions {
+            strict: false,
+            ..get_parse_options()
+        };
+
+        assert_eq!(
+            file.parse::<SecurityTxt>().map_err(|e| e.kind()),
+            Err(ParseErrorKind::Malformed)
+        );
+        assert!(SecurityTxt::parse_with(&file, &options).is_ok());
+
+        let diagnostics = SecurityTxt::diagnose(&file, &options);
+        let warnings: Vec<ParseErrorKind> = diagnostics.warnings().map(|f| f.error.kind()).collect();
+        assert_eq!(warnings, vec![ParseErrorKind::ByteOrderMark]);
+    }
+
+    #[test]
+    fn test_diagnose_valid() {
+        let expires = future_expires_str();
+        let file = format!("Contact: {URL}\nExpires: {expires}\n");
+        let diagnostics = SecurityTxt::diagnose(&file, &get_parse_options());
+
+        assert!(diagnostics.is_valid());
+        assert_eq!(diagnostics.into_result(), file.parse::<SecurityTxt>());
+    }
+
+    #[test]
+    fn test_spans_unsigned() {
+        let expires = future_expires_str();
+        let file = format!("# comment\nContact: {INSECURE_URL}\n  invalid\nExpires: {expires}\n");
+        let diagnostics = SecurityTxt::diagnose(&file, &get_parse_options());
+        let spans: Vec<Option<Span>> = diagnostics.findings.iter().map(|f| f.span().cloned()).collect();
+        let contact_end = 10 + "Contact: ".len() + INSECURE_URL.len();
+
+        assert_eq!(
+            spans,
+            vec![
+                Some(Span {
+                    line: 2,
+                    column: 1,
+                    start: 10,
+                    end: contact_end,
+                }),
+                Some(Span {
+                    line: 3,
+                    column: 1,
+                    start: contact_end + 1,
+                    end: contact_end + 10,
+                }),
+                None,
+            ]
+        );
+
+        let expires_span = diagnostics.partial.expires.unwrap().span.unwrap();
+        assert_eq!(expires_span.line, 4);
+        assert_eq!(&file[expires_span.range()], format!("Expires: {expires}"));
+    }
+
+    #[test]
+    fn test_spans_signed() {
+        let expires = future_expires_str();
+        let file = format!(
+            "-----BEGIN PGP SIGNED MESSAGE-----\r
+Hash: SHA256\r
+\r
+- Contact: {URL}\r
+Expires: {expires}\r
+-----BEGIN PGP SIGNATURE-----\r
+\r
+abcdefABCDEF/+==\r
+-----END PGP SIGNATURE-----\r
+"
+        );
+        let sec = SecurityTxt::parse_with(&file, &get_parse_options()).unwrap();
+
+        let contact_span = sec.contact[0].span.clone().unwrap();
+        assert_eq!((contact_span.line, contact_span.column), (4, 3));
+        assert_eq!(&file[contact_span.range()], format!("Contact: {URL}"));
+
+        let expires_span = sec.expires.span.unwrap();
+        assert_eq!((expires_span.line, expires_span.column), (5, 1));
+        assert_eq!(&file[expires_span.range()], format!("Expires: {expires}"));
+    }
+
+    #[test]
+    fn test_render() {
+        let expires = "2030-04-12T23:20:50.520Z";
+        let file = format!(
+            "Policy: {URL}\nfoo: bar\nContact: mailto:security@example.com\nExpires: {expires}\nContact: {URL}\nPreferred-Languages: en, DE\n"
+        );
+        let sec = SecurityTxt::parse_with(&file, &get_parse_options()).unwrap();
+        let options = RenderOptions::new(vec!["Our security.txt\n\nMaintained by the security team".into()]);
+
+        assert_eq!(
+            sec.render_with(&options),
+            format!(
+                "# Our security.txt\r
+#\r
+# Maintained by the security team\r
+Contact: mailto:security@example.com\r
+Contact: {URL}\r
+Expires: 2030-04-12T23:20:50.520Z\r
+Preferred-Languages: en, de\r
+Policy: {URL}\r
+foo: bar\r
+"
+            )
+        );
+    }
+
+    fn _test_category_roundtrip(category: &str) {
+        let paths = get_tests_dir(category).read_dir().unwrap();
+
+        for path in paths {
+            let buf = fs::read_to_string(path.unwrap().path()).unwrap();
+            let parse_options = get_parse_options();
+            let mut txt = SecurityTxt::parse_with(&buf, &parse_options).unwrap();
+            txt.signature = None;
+            assert_eq!(SecurityTxt::parse_with(&txt.to_string(), &parse_options), Ok(txt));
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_valid_unsigned() {
+        _test_category_roundtrip("valid_unsigned")
+    }
+
+    #[test]
+    fn test_roundtrip_valid_signed() {
+        _test_category_roundtrip("valid_signed")
+    }
+
+    #[test]
+    fn test_roundtrip_gen_unsigned() {
+        _test_category_roundtrip("gen_unsigned")
+    }
+
+    fn uri_strategy() -> impl Strategy<Value = String> {
+        prop_oneof![
+            "[a-z]{1,10}".prop_map(|s| format!("mailto:{s}@example.com")),
+            "[0-9]{5,12}".prop_map(|s| format!("tel:+{s}")),
+            "[a-z0-9/._-]{0,20}".prop_map(|s| format!("https://example.com/{s}")),
+        ]
+    }
+
+    fn uris_strategy(min: usize) -> impl Strategy<Value = Vec<String>> {
+        prop::collection::vec(uri_strategy(), min..4)
+    }
+
+    prop_compose! {
+        fn securitytxt_strategy()(
+            acknowledgments in uris_strategy(0),
+            canonical in uris_strategy(0),
+            contact in uris_strategy(1),
+            csaf in uris_strategy(0),
+            encryption in uris_strategy(0),
+            expires in 1_700_000_000_000i64..4_000_000_000_000i64,
+            extension in prop::collection::vec(("x-[a-z0-9-]{1,10}", "[!-~]{1,10}( [!-~]{1,10})?"), 0..3),
+            hiring in uris_strategy(0),
+            policy in uris_strategy(0),
+            preferred_languages in prop::option::of(prop::sample::subsequence(vec!["en", "de", "fr", "es-MX"], 1..4)),
+        ) -> SecurityTxt {
+            SecurityTxt {
+                acknowledgments: acknowledgments.iter().map(|u| AcknowledgmentsField::new(u).unwrap()).collect(),
+                canonical: canonical.iter().map(|u| CanonicalField::new(u).unwrap()).collect(),
+                contact: contact.iter().map(|u| ContactField::new(u).unwrap()).collect(),
+                csaf: csaf.iter().map(|u| CsafField::new(u).unwrap()).collect(),
+                encryption: encryption.iter().map(|u| EncryptionField::new(u).unwrap()).collect(),
+                expires: ExpiresField::new(&DateTime::from_timestamp_millis(expires).unwrap().to_rfc3339()).unwrap(),
+                extension: extension.into_iter().map(|(n, v)| ExtensionField::new(n, v).unwrap()).collect(),
+                hiring: hiring.iter().map(|u| HiringField::new(u).unwrap()).collect(),
+                policy: policy.iter().map(|u| PolicyField::new(u).unwrap()).collect(),
+                preferred_languages: preferred_languages.map(|l| PreferredLanguagesField::new(&l.join(",")).unwrap()),
+                signature: None,
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_roundtrip_generated(sec in securitytxt_strategy()) {
+            let rendered = sec.to_string();
+            prop_assert_eq!(SecurityTxt::parse_with(&rendered, &get_parse_options()), Ok(sec));
+        }
+    }
+
+    #[test]
+    fn test_lint() {
+        let expires = (some_datetime() + Duration::days(730)).to_rfc3339_opts(SecondsFormat::Millis, true);
+        let file = format!(
+            "Contact: {URL}\nContact: xmpp:security@example.com\nExpires: {expires}\nCanonical: {URL}security.txt\n"
+        );
+        let txt = SecurityTxt::parse_with(&file, &get_parse_options()).unwrap();
+
+        let options = LintOptions {
+            now: some_datetime(),
+            url: Some(format!("{URL}.well-known/security.txt")),
+        };
+        let lints: Vec<(&str, Severity, Option<usize>)> = txt
+            .lint_with(&options)
+            .iter()
+            .map(|l| (l.code(), l.severity, l.span.as_ref().map(|s| s.line)))
+            .collect();
+
+        assert_eq!(
+            lints,
+            vec![
+                ("STXT101", Severity::Warning, Some(3)),
+                ("STXT103", Severity::Warning, Some(4)),
+                ("STXT104", Severity::Warning, Some(2)),
+                ("STXT105", Severity::Info, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_clean() {
+        let expires = (some_datetime() + Duration::days(180)).to_rfc3339_opts(SecondsFormat::Millis, true);
+        let file = format!("Contact: mailto:security@example.com\nExpires: {expires}\nCanonical: {URL}\n");
+        let txt = SecurityTxt::parse_with(&file, &get_parse_options()).unwrap();
+
+        let options = LintOptions {
+            now: some_datetime(),
+            url: Some(URL.to_owned()),
+        };
+        let lints = txt.lint_with(&options);
+
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].kind, LintKind::NotSigned);
+        assert_eq!(lints[0].to_string(), "STXT105: file should be digitally signed");
+    }
+
+    #[test]
+    fn test_explanations() {
+        let parse_error_kinds = [
+            ParseErrorKind::Malformed,
+            ParseErrorKind::InvalidUri,
+            ParseErrorKind::InvalidDatetime,
+            ParseErrorKind::InvalidLanguageTag,
+            ParseErrorKind::IllegalField,
+            ParseErrorKind::ContactFieldMissing,
+            ParseErrorKind::ExpiresFieldMissing,
+            ParseErrorKind::ExpiresFieldExpired,
+            ParseErrorKind::ExpiresFieldExpiresSoon,
+            ParseErrorKind::ExpiresFieldMultiple,
+            ParseErrorKind::PreferredLanguagesFieldMultiple,
+            ParseErrorKind::InsecureHTTP,
+            ParseErrorKind::ByteOrderMark,
+            ParseErrorKind::BareCarriageReturn,
+            ParseErrorKind::MissingFinalLineEnding,
+            ParseErrorKind::TabAfterSeparator,
+            ParseErrorKind::InputTooLarge,
+            ParseErrorKind::InvalidEncoding,
+            ParseErrorKind::Io,
+            ParseErrorKind::MalformedSignedMessage,
+            ParseErrorKind::ReservedExtensionField,
+        ];
+        let lint_kinds = [
+            LintKind::ExpiresTooFarInFuture,
+            LintKind::CanonicalFieldMissing,
+            LintKind::CanonicalMismatch,
+            LintKind::ContactUnusualScheme,
+            LintKind::NotSigned,
+        ];
+
+        let codes: Vec<&str> = parse_error_kinds
+            .iter()
+            .map(|kind| kind.explanation().code)
+            .chain(lint_kinds.iter().map(|kind| kind.explanation().code))
+            .collect();
+        let all: Vec<&str> = Explanation::all().iter().map(|e| e.code).collect();
+        assert_eq!(codes, all);
+        assert!(all.windows(2).all(|w| w[0] < w[1]));
+
+        for kind in lint_kinds {
+            assert_eq!(kind.explanation().summary, kind.to_string());
+        }
+
+        let err = ContactField::new(INSECURE_URL).unwrap_err();
+        assert_eq!(err.code(), "STXT012");
+        assert_eq!(Explanation::find("stxt012"), Some(err.kind().explanation()));
+        assert_eq!(Explanation::find("STXT999"), None);
+    }
+
+    #[test]
+    fn test_matches_canonical() {
+        let file = format!(
+            "Contact: {URL}\nExpires: {}\nCanonical: https://Example.com:443/.well-known/security.txt\nCanonical: https://example.org/security.txt#top\n",
+            future_expires_str()
+        );
+        let txt: SecurityTxt = file.parse().unwrap();
+        let matches = |url: &str| txt.matches_canonical(&url::Url::parse(url).unwrap());
+
+        assert!(matches("https://example.com/.well-known/security.txt"));
+        assert!(matches("https://EXAMPLE.COM/.well-known/security.txt#foo"));
+        assert!(matches("https://example.org/security.txt"));
+        assert!(!matches("https://example.com/security.txt"));
+        assert!(!matches("https://example.net/.well-known/security.txt"));
+        assert!(!matches("http://example.com/.well-known/security.txt"));
+    }
+
+    #[test]
+    fn test_builder() {
+        let expires = future_expires_str();
+        let file = format!("Contact: {URL}\nContact: tel:+1-201-555-0123\nExpires: {expires}\nPolicy: {URL}\nPreferred-Languages: en, de\nfoo: bar\n");
+        let built = SecurityTxtBuilder::new()
+            .contact(URL)
+            .contact("tel:+1-201-555-0123")
+            .expires(expires_dt(&expires).datetime)
+            .policy(URL)
+            .preferred_languages(&["en", "de"])
+            .extension("foo", "bar")
+            .build();
+
+        assert_eq!(built, file.parse());
+    }
+
+    #[test]
+    fn test_builder_validation() {
+        let expires = Utc::now() + Duration::days(365);
+        let test_vector = [
+            (
+                SecurityTxtBuilder::new().expires(expires),
+                ParseErrorKind::ContactFieldMissing,
+            ),
+            (
+                SecurityTxtBuilder::new().contact(URL),
+                ParseErrorKind::ExpiresFieldMissing,
+            ),
+            (
+                SecurityTxtBuilder::new().contact(URL).expires(expires).expires(expires),
+                ParseErrorKind::ExpiresFieldMultiple,
+            ),
+            (
+                SecurityTxtBuilder::new().contact(INSECURE_URL).expires(expires),
+                ParseErrorKind::InsecureHTTP,
+            ),
+            (
+                SecurityTxtBuilder::new()
+                    .contact(URL)
+                    .expires(Utc::now() - Duration::days(1)),
+                ParseErrorKind::ExpiresFieldExpired,
+            ),
+            (
+                SecurityTxtBuilder::new()
+                    .contact(URL)
+                    .expires(expires)
+                    .extension("foo", "bar\r\nContact: https://example.com/"),
+                ParseErrorKind::IllegalField,
+            ),
+            (
+                SecurityTxtBuilder::new()
+                    .contact(URL)
+                    .expires(expires)
+                    .extension("expires", "2000-01-01T00:00:00Z"),
+                ParseErrorKind::ReservedExtensionField,
+            ),
+            (
+                SecurityTxtBuilder::new()
+                    .contact(URL)
+                    .expires(expires)
+                    .extension("Contact", INSECURE_URL),
+                ParseErrorKind::ReservedExtensionField,
+            ),
+        ];
+
+        for (builder, kind) in test_vector {
+            assert_eq!(builder.build().map_err(|err| err.kind()), Err(kind));
+        }
+    }
+
+    #[test]
+    fn test_parse_error_context() {
+        let expires = future_expires_str();
+        let test_vector = [
+            (
+                format!("Contact: {URL}\nPolicy: https://example.com:port/\nExpires: {expires}\n"),
+                ParseErrorKind::InvalidUri,
+                "Policy",
+                "https://example.com:port/",
+                2,
+            ),
+            (
+                format!("Contact: {URL}\nExpires: tomorrow\n"),
+                ParseErrorKind::InvalidDatetime,
+                "Expires",
+                "tomorrow",
+                2,
+            ),
+            (
+                format!("Contact: {URL}\nExpires: {expires}\nPreferred-Languages: en, abcdefghi\n"),
+                ParseErrorKind::InvalidLanguageTag,
+                "Preferred-Languages",
+                "abcdefghi",
+                3,
+            ),
+        ];
+
+        for (file, kind, field, value, line) in test_vector {
+            let err = file.parse::<SecurityTxt>().unwrap_err();
+            assert_eq!(err.kind(), kind);
+            assert_eq!(err.field(), Some(field));
+            assert_eq!(err.value(), Some(value));
+            assert!(err.cause().is_some_and(|cause| !cause.is_empty()));
+            assert!(err.expected().is_some());
+            assert_eq!(err.span().map(|s| s.line), Some(line));
+        }
+
+        let err = ParseError::ExpiresFieldMissing;
+        assert_eq!(
+            (err.field(), err.value(), err.cause(), err.expected()),
+            (None, None, None, None)
+        );
+    }
+
+    fn read_openpgp_resource(name: &str) -> String {
+        fs::read_to_string(get_tests_dir("openpgp").join(name)).unwrap()
+    }
+
+    #[test]
+    fn test_signature_info() {
+        let test_vector = [
+            (
+                "signed_ed25519.stxt",
+                HashAlgorithm::Sha256,
+                PublicKeyAlgorithm::EdDsa,
+                "5E4D122F3789461F38B600DFDF5023392D602E79",
+            ),
+            (
+                "signed_rsa.stxt",
+                HashAlgorithm::Sha512,
+                PublicKeyAlgorithm::Rsa,
+                "8B26FEA362C5EF26676B465B5E3268D2C7F29CA1",
+            ),
+        ];
+
+        for (file, hash_algorithm, public_key_algorithm, fingerprint) in test_vector {
+            let txt = SecurityTxt::parse_with(&read_openpgp_resource(file), &get_parse_options()).unwrap();
+            let signature = txt.signature.unwrap();
+
+            assert_eq!(signature.hash_algorithms, vec![hash_algorithm.to_string()]);
+            assert!(signature.data.is_ok());
+            assert_eq!(signature.hash_algorithm, Some(hash_algorithm));
+            assert_eq!(signature.public_key_algorithm, Some(public_key_algorithm));
+            assert_eq!(signature.issuer_fingerprint.as_deref(), Some(fingerprint));
+            assert_eq!(signature.issuer_key_id.as_deref(), Some(&fingerprint[24..]));
+        }
+    }
+
+    #[test]
+    fn test_signature_info_checksum_mismatch() {
+        let file = read_openpgp_resource("signed_ed25519.stxt");
+        let checksum = file.lines().find(|l| l.starts_with('=')).unwrap();
+        let corrupted = file.replace(checksum, "=AAAA");
+
+        let txt = SecurityTxt::parse_with(&corrupted, &get_parse_options()).unwrap();
+        let signature = txt.signature.unwrap();
+        assert_eq!(signature.data, Err(SignatureError::ChecksumMismatch));
+        assert_eq!(signature.issuer_fingerprint, None);
+    }
+
+    #[test]
+    fn test_unsigned_has_no_signature() {
+        let expires = future_expires_str();
+        let file = format!("Contact: {URL}\nExpires: {expires}\n");
+        let txt: SecurityTxt = file.parse().unwrap();
+
+        assert_eq!(txt.signature, None);
+    }
+
+    #[cfg(feature = "openpgp")]
+    #[test]
+    fn test_verify_signature() {
+        let test_vector = [
+            (
+                "signed_ed25519.stxt",
+                "ed25519_public.asc",
+                "5E4D122F3789461F38B600DFDF5023392D602E79",
+                HashAlgorithm::Sha256,
+            ),
+            (
+                "signed_rsa.stxt",
+                "rsa_public.asc",
+                "8B26FEA362C5EF26676B465B5E3268D2C7F29CA1",
+                HashAlgorithm::Sha512,
+            ),
+        ];
+
+        for (file, key, fingerprint, hash_algorithm) in test_vector {
+            let keyring = Keyring::from_armored(&read_openpgp_resource(key)).unwrap();
+            let verification = keyring.verify(&read_openpgp_resource(file), &get_parse_options());
+
+            assert_eq!(
+                verification,
+                Ok(SignatureVerification {
+                    fingerprint: Some(fingerprint.into()),
+                    key_id: Some(fingerprint[24..].into()),
+                    hash_algorithm,
+                    validity: SignatureValidity::Valid,
+                })
+            );
+        }
+    }
+
+    #[cfg(feature = "openpgp")]
+    #[test]
+    fn test_verify_signature_failures() {
+        let mut keyring = Keyring::new();
+        keyring.add_armored(&read_openpgp_resource("rsa_public.asc")).unwrap();
+        keyring
+            .add_armored(&read_openpgp_resource("ed25519_public.asc"))
+            .unwrap();
+        assert_eq!(keyring.len(), 2);
+
+        let file = read_openpgp_resource("signed_ed25519.stxt");
+        let tampered = file.replace("mailto:security@", "mailto:attacker@");
+        let verification = keyring.verify(&tampered, &get_parse_options()).unwrap();
+        assert_eq!(verification.validity, SignatureValidity::Invalid);
+
+        let keyring = Keyring::from_armored(&read_openpgp_resource("rsa_public.asc")).unwrap();
+        let verification = keyring.verify(&file, &get_parse_options()).unwrap();
+        assert_eq!(verification.validity, SignatureValidity::UnknownKey);
+        assert_eq!(
+            verification.fingerprint.as_deref(),
+            Some("5E4D122F3789461F38B600DFDF5023392D602E79")
+        );
+
+        let checksum = file.lines().find(|l| l.starts_with('=')).unwrap();
+        let corrupted = file.replace(checksum, "=AAAA");
+        assert_eq!(
+            keyring.verify(&corrupted, &get_parse_options()),
+            Err(SignatureError::ChecksumMismatch)
+        );
+
+        let unsigned = format!("Contact: {URL}\nExpires: {}\n", future_expires_str());
+        assert_eq!(
+            keyring.verify(&unsigned, &get_parse_options()),
+            Err(SignatureError::NotSigned)
+        );
+    }
+
+    #[cfg(feature = "openpgp")]
+    #[test]
+    fn test_verify_hash_header_mismatch() {
+        let keyring = Keyring::from_armored(&read_openpgp_resource("ed25519_public.asc")).unwrap();
+        let file = read_openpgp_resource("signed_ed25519.stxt").replace("Hash: SHA256", "Hash: SHA512");
+
+        assert_eq!(
+            keyring.verify(&file, &get_parse_options()),
+            Err(SignatureError::HashHeaderMismatch(HashAlgorithm::Sha256))
+        );
+    }
+
+    #[cfg(feature = "openpgp")]
+    #[test]
+    fn test_verify_insecure_hash() {
+        use pgp::composed::{CleartextSignedMessage, Deserializable, SignedSecretKey};
+        use pgp::packet::{SignatureConfig, SignatureType, Subpacket, SubpacketData};
+        use pgp::types::{KeyDetails, Password};
+
+        let (key, _) = SignedSecretKey::from_string(&read_openpgp_resource("rsa_secret.asc")).unwrap();
+        let mut config = SignatureConfig::v4(
+            SignatureType::Text,
+            key.algorithm(),
+            pgp::crypto::hash::HashAlgorithm::Sha1,
+        );
+        config.hashed_subpackets =
+            vec![Subpacket::regular(SubpacketData::IssuerFingerprint(key.fingerprint())).unwrap()];
+        let text = format!("Contact: {URL}\nExpires: {}\n", future_expires_str());
+        let signed = CleartextSignedMessage::new(&text, config, &key.primary_key, &Password::empty())
+            .unwrap()
+            .to_armored_string(Default::default())
+            .unwrap();
+
+        let keyring = Keyring::from_armored(&read_openpgp_resource("rsa_public.asc")).unwrap();
+        assert_eq!(
+            keyring.verify(&signed, &get_parse_options()),
+            Err(SignatureError::InsecureHashAlgorithm(HashAlgorithm::Sha1))
+        );
+
+        let verification = keyring
+            .allow_insecure_hashes(true)
+            .verify(&signed, &get_parse_options())
+            .unwrap();
+        assert_eq!(verification.validity, SignatureValidity::Valid);
+    }
+
+    #[cfg(feature = "openpgp")]
+    #[test]
+    fn test_sign() {
+        let test_vector = [
+            (
+                "ed25519_secret.asc",
+                "ed25519_public.asc",
+                "5E4D122F3789461F38B600DFDF5023392D602E79",
+            ),
+            (
+                "rsa_secret.asc",
+                "rsa_public.asc",
+                "8B26FEA362C5EF26676B465B5E3268D2C7F29CA1",
+            ),
+        ];
+
+        let txt = SecurityTxtBuilder::new()
+            .contact("mailto:security@example.com")
+            .expires(some_datetime() + Duration::days(365))
+            .build_with(&get_parse_options())
+            .unwrap();
+
+        for (secret, public, fingerprint) in test_vector {
+            let key = SigningKey::from_armored(&read_openpgp_resource(secret)).unwrap();
+            assert_eq!(key.fingerprint(), fingerprint);
+
+            let signed = txt.sign(&key).unwrap();
+
+            let mut parsed = SecurityTxt::parse_with(&signed, &get_parse_options()).unwrap();
+            let signature = parsed.signature.take().unwrap();
+            assert_eq!(parsed, txt);
+            assert_eq!(signature.hash_algorithms, vec!["SHA512"]);
+            assert_eq!(signature.issuer_fingerprint.as_deref(), Some(fingerprint));
+
+            let keyring = Keyring::from_armored(&read_openpgp_resource(public)).unwrap();
+            let verification = keyring.verify(&signed, &get_parse_options()).unwrap();
+            assert_eq!(verification.validity, SignatureValidity::Valid);
+        }
+    }
+
+    #[cfg(feature = "openpgp")]
+    #[test]
+    fn test_sign_roundtrip() {
+        let txt = SecurityTxtBuilder::new()
+            .contact("mailto:security@example.com")
+            .expires(Utc::now() + Duration::days(180))
+            .build()
+            .unwrap();
+
+        for (secret, public) in [
+            ("ed25519_secret.asc", "ed25519_public.asc"),
+            ("rsa_secret.asc", "rsa_public.asc"),
+        ] {
+            let key = SigningKey::from_armored(&read_openpgp_resource(secret)).unwrap();
+            let signed = txt.sign(&key).unwrap();
+
+            let parsed = SecurityTxt::parse(&signed).unwrap();
+            assert_eq!(parsed.contact, txt.contact);
+            assert!(parsed.signature.is_some());
+
+            let keyring = Keyring::from_armored(&read_openpgp_resource(public)).unwrap();
+            let verification = keyring.verify(&signed, &Default::default()).unwrap();
+            assert_eq!(verification.validity, SignatureValidity::Valid);
+            assert_eq!(verification.fingerprint, Some(key.fingerprint()));
+        }
+    }
+
+    #[cfg(feature = "openpgp")]
+    #[test]
+    fn test_sign_dash_escaped() {
+        let key = SigningKey::from_armored(&read_openpgp_resource("ed25519_secret.asc")).unwrap();
+        let keyring = Keyring::from_armored(&read_openpgp_resource("ed25519_public.asc")).unwrap();
+
+        let signed = key.sign("- a\n-- b \t\nc\n").unwrap();
+        assert!(signed.contains("\r\n\r\n- - a\r\n- -- b \t\r\nc\r\n-----BEGIN PGP SIGNATURE-----\r\n"));
+
+        let verification = keyring.verify(&signed, &get_parse_options()).unwrap();
+        assert_eq!(verification.validity, SignatureValidity::Valid);
+    }
+
+    #[cfg(feature = "openpgp")]
+    #[test]
+    fn test_signing_key_missing() {
+        let public = read_openpgp_resource("ed25519_public.asc");
+
+        assert_eq!(
+            SigningKey::from_armored(&public).unwrap_err(),
+            SignatureError::SecretKeyMissing
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() {
+        let mut files: Vec<String> = ["valid_unsigned", "valid_signed", "openpgp"]
+            .iter()
+            .flat_map(|category| get_tests_dir(category).read_dir().unwrap())
+            .map(|path| fs::read_to_string(path.unwrap().path()).unwrap())
+            .collect();
+        files.retain(|buf| SecurityTxt::parse_with(buf, &get_parse_options()).is_ok());
+        assert!(!files.is_empty());
+
+        for buf in files {
+            let txt = SecurityTxt::parse_with(&buf, &get_parse_options()).unwrap();
+            let json = serde_json::to_string(&txt).unwrap();
+            let deserialized: SecurityTxt = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(deserialized, txt);
+            assert_eq!(deserialized.contact[0].span, txt.contact[0].span);
+            assert_eq!(deserialized.expires.span, txt.expires.span);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_schema() {
+        let file = format!("Contact: {URL}\nExpires: 2030-04-12T23:20:50.520Z\nPreferred-Languages: en\nfoo: bar\n");
+        let txt = SecurityTxt::parse_with(&file, &get_parse_options()).unwrap();
+        let span = |line: usize, start: usize, end: usize| serde_json::json!({"line": line, "column": 1, "start": start, "end": end});
+
+        assert_eq!(
+            serde_json::to_value(&txt).unwrap(),
+            serde_json::json!({
+                "acknowledgments": [],
+                "canonical": [],
+                "contact": [{"uri": URL, "span": span(1, 0, 33)}],
+                "csaf": [],
+                "encryption": [],
+                "expires": {"datetime": "2030-04-12T23:20:50.520Z", "span": span(2, 34, 67)},
+                "extension": [{"name": "foo", "value": "bar", "span": span(4, 92, 100)}],
+                "hiring": [],
+                "policy": [],
+                "preferred_languages": {"languages": ["en"], "span": span(3, 68, 91)},
+                "signature": null,
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_validation() {
+        let insecure = serde_json::json!({"uri": INSECURE_URL});
+        assert!(serde_json::from_value::<ContactField>(insecure).is_err());
+
+        let languages = serde_json::json!({"languages": []});
+        assert!(serde_json::from_value::<PreferredLanguagesField>(languages).is_err());
+
+        let field: ContactField = serde_json::from_value(serde_json::json!({"uri": URL})).unwrap();
+        assert_eq!(field, ContactField::new(URL).unwrap());
+        assert_eq!(field.span, None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_parse_error() {
+        let errors = [
+            ContactField::new(INSECURE_URL).unwrap_err(),
+            ExpiresField::new("tomorrow").unwrap_err(),
+            ParseError::ExpiresFieldExpired {
+                value: "2000-01-01T00:00:00+00:00".to_owned(),
+                span: None,
+            },
+            "Contact: http://example.com\n".parse::<SecurityTxt>().unwrap_err(),
+            "foo\n".parse::<SecurityTxt>().unwrap_err(),
+            SecurityTxt::parse_bytes(b"Contact: \xFF\n").unwrap_err(),
+        ];
+        for err in errors {
+            let json = serde_json::to_string(&err).unwrap();
+            assert_eq!(serde_json::from_str::<ParseError>(&json).unwrap(), err);
+        }
+
+        let err = ContactField::new(INSECURE_URL).unwrap_err();
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            serde_json::json!({"kind": "InsecureHTTP", "field": "Contact", "value": INSECURE_URL, "cause": null, "span": null})
+        );
+        assert!(serde_json::from_str::<ParseError>(r#"{"kind": "Unknown"}"#).is_err());
+    }
 }
@@ -0,0 +1,151 @@
+use super::diagnostics::Severity;
+use super::securitytxt::SecurityTxt;
+use super::span::Span;
+use chrono::{DateTime, Duration, Utc};
+use std::fmt;
+use thiserror::Error;
+use url::Url;
+
+/// A recommendation of [RFC 9116](https://www.rfc-editor.org/rfc/rfc9116) which a valid security.txt file does not
+/// follow
+#[derive(Error, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LintKind {
+    #[error("expires field is more than a year in the future")]
+    ExpiresTooFarInFuture,
+    #[error("canonical field should be specified")]
+    CanonicalFieldMissing,
+    #[error("canonical fields do not include the URL the file was retrieved from")]
+    CanonicalMismatch,
+    #[error("contact field should use a mailto, tel, or https URI")]
+    ContactUnusualScheme,
+    #[error("file should be digitally signed")]
+    NotSigned,
+}
+
+impl LintKind {
+    /// Returns the stable code identifying this kind of lint, e.g., "STXT101"
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ExpiresTooFarInFuture => "STXT101",
+            Self::CanonicalFieldMissing => "STXT102",
+            Self::CanonicalMismatch => "STXT103",
+            Self::ContactUnusualScheme => "STXT104",
+            Self::NotSigned => "STXT105",
+        }
+    }
+
+    /// Returns the severity of this kind of lint
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::ExpiresTooFarInFuture => Severity::Warning,
+            Self::CanonicalFieldMissing => Severity::Info,
+            Self::CanonicalMismatch => Severity::Warning,
+            Self::ContactUnusualScheme => Severity::Warning,
+            Self::NotSigned => Severity::Info,
+        }
+    }
+}
+
+/// A single recommendation which a valid security.txt file does not follow
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lint {
+    /// The recommendation which is not followed
+    pub kind: LintKind,
+
+    /// How severe the lint is
+    pub severity: Severity,
+
+    /// The location of the offending field in the parsed file, if it relates to a specific field
+    pub span: Option<Span>,
+}
+
+impl Lint {
+    fn new(kind: LintKind, span: Option<Span>) -> Self {
+        Self {
+            kind,
+            severity: kind.severity(),
+            span,
+        }
+    }
+
+    /// Returns the stable code identifying this lint
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code(), self.kind)
+    }
+}
+
+/// Options for linting a security.txt file
+#[derive(Clone, Debug)]
+pub struct LintOptions {
+    /// The current date and time to check the "Expires" field against
+    pub now: DateTime<Utc>,
+
+    /// The URL the file was retrieved from, if known, which should be listed in a "Canonical" field
+    ///
+    /// See [`SecurityTxt::matches_canonical`] for how the URLs are compared.
+    pub url: Option<String>,
+}
+
+impl LintOptions {
+    pub fn new(url: Option<String>) -> Self {
+        Self { now: Utc::now(), url }
+    }
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+/// URI schemes recommended for "Contact" fields by [RFC 9116, Section 2.5.3](https://www.rfc-editor.org/rfc/rfc9116#name-contact)
+const CONTACT_SCHEMES: [&str; 3] = ["mailto", "tel", "https"];
+
+impl SecurityTxt {
+    /// Checks the file against the recommendations of [RFC 9116](https://www.rfc-editor.org/rfc/rfc9116).
+    ///
+    /// Unlike parsing errors, lints do not make the file invalid.
+    pub fn lint(&self) -> Vec<Lint> {
+        self.lint_with(&Default::default())
+    }
+
+    /// Checks the file against the recommendations of [RFC 9116](https://www.rfc-editor.org/rfc/rfc9116) with the
+    /// given options
+    pub fn lint_with(&self, options: &LintOptions) -> Vec<Lint> {
+        let mut lints = vec![];
+
+        // The RFC recommends an expiry of less than a year into the future to avoid staleness.
+        if self.expires.datetime > options.now + Duration::days(365) {
+            lints.push(Lint::new(LintKind::ExpiresTooFarInFuture, self.expires.span.clone()));
+        }
+
+        if self.canonical.is_empty() {
+            lints.push(Lint::new(LintKind::CanonicalFieldMissing, None));
+        } else if let Some(url) = &options.url {
+            let matches = Url::parse(url).is_ok_and(|url| self.matches_canonical(&url));
+            if !matches {
+                let span = self.canonical.first().and_then(|c| c.span.clone());
+                lints.push(Lint::new(LintKind::CanonicalMismatch, span));
+            }
+        }
+
+        for contact in &self.contact {
+            let scheme = contact.uri.scheme_str();
+            if !CONTACT_SCHEMES.iter().any(|s| s.eq_ignore_ascii_case(scheme)) {
+                lints.push(Lint::new(LintKind::ContactUnusualScheme, contact.span.clone()));
+            }
+        }
+
+        if self.signature.is_none() {
+            lints.push(Lint::new(LintKind::NotSigned, None));
+        }
+
+        lints
+    }
+}
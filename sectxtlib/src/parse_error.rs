@@ -1,39 +1,378 @@
-use iri_string::validate;
-use oxilangtag::LanguageTagParseError;
+use super::span::{SourceMap, Span};
+use nom::Offset;
 use thiserror::Error;
 
+/// An error encountered while parsing or validating a security.txt file
+///
+/// Errors concerning a single field carry the name of the field, the offending value, and the underlying cause. Use
+/// [`ParseError::kind`] to match on errors regardless of their context.
 #[derive(Error, Debug, PartialEq)]
 pub enum ParseError {
-    #[error("invalid syntax")]
-    Malformed,
-    #[error("invalid date format")]
-    InvalidDatetime(#[from] chrono::format::ParseError),
-    #[error("field specified in an illegal way")]
-    IllegalField,
+    #[error("invalid syntax in line {value:?}")]
+    Malformed { value: String, span: Option<Span> },
+    #[error("invalid URI {value:?} in {field} field: {cause}")]
+    InvalidUri {
+        field: String,
+        value: String,
+        cause: String,
+        span: Option<Span>,
+    },
+    #[error("invalid date {value:?} in {field} field: {cause}")]
+    InvalidDatetime {
+        field: String,
+        value: String,
+        cause: String,
+        span: Option<Span>,
+    },
+    #[error("invalid language tag {value:?} in {field} field: {cause}")]
+    InvalidLanguageTag {
+        field: String,
+        value: String,
+        cause: String,
+        span: Option<Span>,
+    },
+    #[error("field {field:?} specified in an illegal way: {cause}")]
+    IllegalField {
+        field: String,
+        value: String,
+        cause: String,
+        span: Option<Span>,
+    },
+    #[error("field {field:?} is a standard field and cannot be added as an extension")]
+    ReservedExtensionField { field: String, span: Option<Span> },
     #[error("contact field must be specified")]
     ContactFieldMissing,
     #[error("expires field must be specified")]
     ExpiresFieldMissing,
-    #[error("expires field specifies time in the past")]
-    ExpiresFieldExpired,
+    #[error("expires field specifies time in the past: {value}")]
+    ExpiresFieldExpired { value: String, span: Option<Span> },
+    #[error("expires field specifies time in the near future: {value}")]
+    ExpiresFieldExpiresSoon { value: String, span: Option<Span> },
     #[error("expires field may only be specified once")]
-    ExpiresFieldMultiple,
+    ExpiresFieldMultiple { span: Option<Span> },
     #[error("preferred languages field may only be specified once")]
+    PreferredLanguagesFieldMultiple { span: Option<Span> },
+    #[error("links must use HTTPS, but {field} field contains {value:?}")]
+    InsecureHTTP {
+        field: String,
+        value: String,
+        span: Option<Span>,
+    },
+    #[error("file starts with a byte order mark")]
+    ByteOrderMark { span: Option<Span> },
+    #[error("line ends with a bare carriage return")]
+    BareCarriageReturn { span: Option<Span> },
+    #[error("last line does not end with a line break")]
+    MissingFinalLineEnding { span: Option<Span> },
+    #[error("field separator is followed by a tab instead of a space")]
+    TabAfterSeparator { span: Option<Span> },
+    #[error("input exceeds the maximum size")]
+    InputTooLarge,
+    #[error("invalid encoding at line {}, column {}: {cause}", .span.line, .span.column)]
+    InvalidEncoding { span: Span, cause: String },
+    #[error("failed to read input: {cause}")]
+    Io { cause: String },
+    #[error("invalid OpenPGP cleartext signature framework in line {value:?}")]
+    MalformedSignedMessage { value: String, span: Option<Span> },
+}
+
+/// The kind of a [`ParseError`], which stays the same regardless of the context an error carries
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParseErrorKind {
+    Malformed,
+    InvalidUri,
+    InvalidDatetime,
+    InvalidLanguageTag,
+    IllegalField,
+    ContactFieldMissing,
+    ExpiresFieldMissing,
+    ExpiresFieldExpired,
+    ExpiresFieldExpiresSoon,
+    ExpiresFieldMultiple,
     PreferredLanguagesFieldMultiple,
-    #[error("links must use HTTPS")]
     InsecureHTTP,
+    ByteOrderMark,
+    BareCarriageReturn,
+    MissingFinalLineEnding,
+    TabAfterSeparator,
+    InputTooLarge,
+    InvalidEncoding,
+    Io,
+    MalformedSignedMessage,
+    ReservedExtensionField,
 }
 
-macro_rules! impl_from {
-    ( $for:path, $from:path, $to:path  ) => {
-        impl From<$from> for $for {
-            fn from(_: $from) -> $for {
-                $to
-            }
+impl ParseError {
+    /// Returns the kind of the error
+    pub fn kind(&self) -> ParseErrorKind {
+        match self {
+            Self::Malformed { .. } => ParseErrorKind::Malformed,
+            Self::InvalidUri { .. } => ParseErrorKind::InvalidUri,
+            Self::InvalidDatetime { .. } => ParseErrorKind::InvalidDatetime,
+            Self::InvalidLanguageTag { .. } => ParseErrorKind::InvalidLanguageTag,
+            Self::IllegalField { .. } => ParseErrorKind::IllegalField,
+            Self::ReservedExtensionField { .. } => ParseErrorKind::ReservedExtensionField,
+            Self::ContactFieldMissing => ParseErrorKind::ContactFieldMissing,
+            Self::ExpiresFieldMissing => ParseErrorKind::ExpiresFieldMissing,
+            Self::ExpiresFieldExpired { .. } => ParseErrorKind::ExpiresFieldExpired,
+            Self::ExpiresFieldExpiresSoon { .. } => ParseErrorKind::ExpiresFieldExpiresSoon,
+            Self::ExpiresFieldMultiple { .. } => ParseErrorKind::ExpiresFieldMultiple,
+            Self::PreferredLanguagesFieldMultiple { .. } => ParseErrorKind::PreferredLanguagesFieldMultiple,
+            Self::InsecureHTTP { .. } => ParseErrorKind::InsecureHTTP,
+            Self::ByteOrderMark { .. } => ParseErrorKind::ByteOrderMark,
+            Self::BareCarriageReturn { .. } => ParseErrorKind::BareCarriageReturn,
+            Self::MissingFinalLineEnding { .. } => ParseErrorKind::MissingFinalLineEnding,
+            Self::TabAfterSeparator { .. } => ParseErrorKind::TabAfterSeparator,
+            Self::InputTooLarge => ParseErrorKind::InputTooLarge,
+            Self::InvalidEncoding { .. } => ParseErrorKind::InvalidEncoding,
+            Self::Io { .. } => ParseErrorKind::Io,
+            Self::MalformedSignedMessage { .. } => ParseErrorKind::MalformedSignedMessage,
         }
-    };
+    }
+
+    /// Returns the stable code identifying the kind of the error, e.g., "STXT001"
+    pub fn code(&self) -> &'static str {
+        self.kind().code()
+    }
+
+    /// Returns the name of the field the error concerns, if it concerns a single field
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            Self::InvalidUri { field, .. }
+            | Self::InvalidDatetime { field, .. }
+            | Self::InvalidLanguageTag { field, .. }
+            | Self::IllegalField { field, .. }
+            | Self::ReservedExtensionField { field, .. }
+            | Self::InsecureHTTP { field, .. } => Some(field),
+            Self::ExpiresFieldExpired { .. } | Self::ExpiresFieldExpiresSoon { .. } => Some("Expires"),
+            _ => None,
+        }
+    }
+
+    /// Returns the offending value of the field, if the error concerns a single field
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            Self::InvalidUri { value, .. }
+            | Self::InvalidDatetime { value, .. }
+            | Self::InvalidLanguageTag { value, .. }
+            | Self::IllegalField { value, .. }
+            | Self::InsecureHTTP { value, .. }
+            | Self::ExpiresFieldExpired { value, .. }
+            | Self::ExpiresFieldExpiresSoon { value, .. }
+            | Self::Malformed { value, .. }
+            | Self::MalformedSignedMessage { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a description of the underlying cause, e.g., the rule of the URI syntax that was violated
+    pub fn cause(&self) -> Option<&str> {
+        match self {
+            Self::InvalidUri { cause, .. }
+            | Self::InvalidDatetime { cause, .. }
+            | Self::InvalidLanguageTag { cause, .. }
+            | Self::IllegalField { cause, .. }
+            | Self::InvalidEncoding { cause, .. }
+            | Self::Io { cause } => Some(cause),
+            _ => None,
+        }
+    }
+
+    /// Returns the location of the error in the original input, if it is known
+    ///
+    /// Errors concerning the file as a whole, e.g., a missing field, have no location. Neither have errors concerning
+    /// a field which was not parsed from a file, e.g., when using a [`SecurityTxtBuilder`](crate::SecurityTxtBuilder).
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            Self::Malformed { span, .. }
+            | Self::MalformedSignedMessage { span, .. }
+            | Self::InvalidUri { span, .. }
+            | Self::InvalidDatetime { span, .. }
+            | Self::InvalidLanguageTag { span, .. }
+            | Self::IllegalField { span, .. }
+            | Self::ReservedExtensionField { span, .. }
+            | Self::ExpiresFieldExpired { span, .. }
+            | Self::ExpiresFieldExpiresSoon { span, .. }
+            | Self::ExpiresFieldMultiple { span }
+            | Self::PreferredLanguagesFieldMultiple { span }
+            | Self::ByteOrderMark { span }
+            | Self::BareCarriageReturn { span }
+            | Self::MissingFinalLineEnding { span }
+            | Self::TabAfterSeparator { span }
+            | Self::InsecureHTTP { span, .. } => span.as_ref(),
+            Self::InvalidEncoding { span, .. } => Some(span),
+            _ => None,
+        }
+    }
+
+    /// Sets the location of the error, if errors of its kind can be located
+    pub(crate) fn with_span(mut self, location: Option<Span>) -> Self {
+        match &mut self {
+            Self::Malformed { span, .. }
+            | Self::MalformedSignedMessage { span, .. }
+            | Self::InvalidUri { span, .. }
+            | Self::InvalidDatetime { span, .. }
+            | Self::InvalidLanguageTag { span, .. }
+            | Self::IllegalField { span, .. }
+            | Self::ReservedExtensionField { span, .. }
+            | Self::ExpiresFieldExpired { span, .. }
+            | Self::ExpiresFieldExpiresSoon { span, .. }
+            | Self::ExpiresFieldMultiple { span }
+            | Self::PreferredLanguagesFieldMultiple { span }
+            | Self::ByteOrderMark { span }
+            | Self::BareCarriageReturn { span }
+            | Self::MissingFinalLineEnding { span }
+            | Self::TabAfterSeparator { span }
+            | Self::InsecureHTTP { span, .. } => *span = location,
+            _ => {}
+        }
+
+        self
+    }
+
+    /// Returns the grammar production the offending input was expected to match, if the error is a syntax error
+    pub fn expected(&self) -> Option<&'static str> {
+        self.kind().expected()
+    }
+}
+
+impl ParseErrorKind {
+    /// Returns the grammar production the offending input was expected to match, if errors of this kind are syntax errors
+    pub fn expected(&self) -> Option<&'static str> {
+        match self {
+            Self::Malformed => Some("body as defined in RFC 9116, Section 4"),
+            Self::InvalidUri => Some("URI as defined in RFC 3986, Section 3"),
+            Self::InvalidDatetime => Some("date-time as defined in RFC 3339, Section 5.6"),
+            Self::InvalidLanguageTag => Some("Language-Tag as defined in RFC 5646, Section 2.1"),
+            Self::IllegalField => Some("field as defined in RFC 9116, Section 4"),
+            Self::InsecureHTTP => Some("URI with the \"https\" scheme as required by RFC 9116, Section 2.5"),
+            Self::InvalidEncoding => Some("UTF-8 as defined in RFC 3629"),
+            Self::MalformedSignedMessage => Some("cleartext signature framework as defined in RFC 4880, Section 7"),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the byte offset in `text` at which a parser failed
+fn error_offset(text: &str, err: &nom::Err<nom::error::Error<&str>>) -> usize {
+    match err {
+        nom::Err::Error(err) | nom::Err::Failure(err) => text.offset(err.input),
+        nom::Err::Incomplete(_) => text.len(),
+    }
 }
 
-impl_from!(ParseError, validate::Error, ParseError::Malformed);
-impl_from!(ParseError, nom::Err<nom::error::Error<&str>>, ParseError::Malformed);
-impl_from!(ParseError, LanguageTagParseError, ParseError::Malformed);
+/// Returns the byte range of the line of `text` which contains the byte offset `offset`, without its line ending
+fn line_at(text: &str, offset: usize) -> std::ops::Range<usize> {
+    let start = text[..offset].rfind(['\n', '\r']).map_or(0, |i| i + 1);
+    let end = text[offset..].find(['\n', '\r']).map_or(text.len(), |i| offset + i);
+    start..end
+}
+
+impl ParseError {
+    /// Creates a syntax error for the line of the parsed text at which the parser failed
+    pub(crate) fn malformed(text: &str, err: &nom::Err<nom::error::Error<&str>>, source: &SourceMap) -> Self {
+        let line = line_at(text, error_offset(text, err));
+
+        Self::Malformed {
+            value: text[line.clone()].to_owned(),
+            span: Some(source.span(line)),
+        }
+    }
+
+    /// Creates an error for the line of a signed message at which the parser of its framework failed
+    pub(crate) fn malformed_signed_message(text: &str, err: &nom::Err<nom::error::Error<&str>>) -> Self {
+        let line = line_at(text, error_offset(text, err));
+
+        Self::MalformedSignedMessage {
+            value: text[line.clone()].to_owned(),
+            span: Some(SourceMap::new(text).span(line)),
+        }
+    }
+}
+
+// With the `serde` feature, errors are represented by their kind together with their context. Context which an error
+// does not carry is `null`.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ParseErrorRepr<T> {
+    kind: ParseErrorKind,
+    field: Option<T>,
+    value: Option<T>,
+    cause: Option<T>,
+    span: Option<Span>,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ParseError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ParseErrorRepr {
+            kind: self.kind(),
+            field: self.field(),
+            value: self.value(),
+            cause: self.cause(),
+            span: self.span().cloned(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ParseError {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ParseErrorRepr::<String>::deserialize(deserializer)?;
+        let field = repr.field.unwrap_or_default();
+        let value = repr.value.unwrap_or_default();
+        let cause = repr.cause.unwrap_or_default();
+        let span = repr.span;
+
+        let err = match repr.kind {
+            ParseErrorKind::Malformed => Self::Malformed { value, span },
+            ParseErrorKind::InvalidUri => Self::InvalidUri {
+                field,
+                value,
+                cause,
+                span,
+            },
+            ParseErrorKind::InvalidDatetime => Self::InvalidDatetime {
+                field,
+                value,
+                cause,
+                span,
+            },
+            ParseErrorKind::InvalidLanguageTag => Self::InvalidLanguageTag {
+                field,
+                value,
+                cause,
+                span,
+            },
+            ParseErrorKind::IllegalField => Self::IllegalField {
+                field,
+                value,
+                cause,
+                span,
+            },
+            ParseErrorKind::ReservedExtensionField => Self::ReservedExtensionField { field, span },
+            ParseErrorKind::ContactFieldMissing => Self::ContactFieldMissing,
+            ParseErrorKind::ExpiresFieldMissing => Self::ExpiresFieldMissing,
+            ParseErrorKind::ExpiresFieldExpired => Self::ExpiresFieldExpired { value, span },
+            ParseErrorKind::ExpiresFieldExpiresSoon => Self::ExpiresFieldExpiresSoon { value, span },
+            ParseErrorKind::ExpiresFieldMultiple => Self::ExpiresFieldMultiple { span },
+            ParseErrorKind::PreferredLanguagesFieldMultiple => Self::PreferredLanguagesFieldMultiple { span },
+            ParseErrorKind::InsecureHTTP => Self::InsecureHTTP { field, value, span },
+            ParseErrorKind::ByteOrderMark => Self::ByteOrderMark { span },
+            ParseErrorKind::BareCarriageReturn => Self::BareCarriageReturn { span },
+            ParseErrorKind::MissingFinalLineEnding => Self::MissingFinalLineEnding { span },
+            ParseErrorKind::TabAfterSeparator => Self::TabAfterSeparator { span },
+            ParseErrorKind::InputTooLarge => Self::InputTooLarge,
+            ParseErrorKind::InvalidEncoding => Self::InvalidEncoding {
+                span: span.ok_or_else(|| serde::de::Error::missing_field("span"))?,
+                cause,
+            },
+            ParseErrorKind::Io => Self::Io { cause },
+            ParseErrorKind::MalformedSignedMessage => Self::MalformedSignedMessage { value, span },
+        };
+
+        Ok(err)
+    }
+}
@@ -1,37 +1,124 @@
 use crate::{ParseError, SecurityTxtOptions};
 
 use super::raw_field::RawField;
+use super::span::SourceMap;
 
 use nom::{
     branch::alt,
-    bytes::complete::{take_while, take_while1},
+    bytes::complete::{tag, take_while, take_while1},
     character::complete::{char, crlf, satisfy},
-    combinator::{all_consuming, map, opt, recognize},
-    multi::{many0_count, many1},
+    combinator::{all_consuming, eof, map, opt, recognize},
+    error::{Error, ErrorKind},
+    multi::{fold_many1, many0_count},
     sequence::{preceded, terminated},
-    IResult, Parser,
+    IResult, Offset, Parser,
 };
+use std::ops::Range;
 
+/// The byte order mark, which is accepted at the start of a file in lenient mode
+pub(crate) const BOM: char = '\u{FEFF}';
+
+/// The unsigned security.txt grammar of [RFC 9116, Section 4](https://www.rfc-editor.org/rfc/rfc9116#name-file-format-description-and)
+///
+/// Unless the `strict` option is set, a lenient variant of the grammar is used which additionally accepts a bare CR
+/// as line ending, a missing line ending at the end of the file, a byte order mark at the start of the file, and a tab
+/// instead of a space after the field separator.
 pub(crate) struct SecurityTxtParser {
-    _options: SecurityTxtOptions,
+    options: SecurityTxtOptions,
 }
 
 impl SecurityTxtParser {
     pub fn new(options: &SecurityTxtOptions) -> Self {
         Self {
-            _options: options.clone(),
+            options: options.clone(),
+        }
+    }
+
+    /// Parses the fields of the given text, which is mapped to the original input by `source`
+    pub fn parse<'a>(&'a self, text: &'a str, source: &SourceMap) -> Result<Vec<RawField<'a>>, ParseError> {
+        match self.body_parser(text) {
+            Ok((_, fields)) => Ok(fields),
+            Err(err) => Err(ParseError::malformed(text, &err, source)),
+        }
+    }
+
+    /// Parses the input line by line, skipping over lines which do not adhere to the grammar.
+    ///
+    /// Returns the parsed fields along with all lines which could not be parsed.
+    pub fn parse_recovering<'a>(&'a self, text: &'a str) -> (Vec<RawField<'a>>, Vec<&'a str>) {
+        let mut fields = vec![];
+        let mut malformed = vec![];
+        let mut i = match self.options.strict {
+            true => text,
+            false => text.strip_prefix(BOM).unwrap_or(text),
+        };
+
+        while !i.is_empty() {
+            match self.line_parser(i) {
+                Ok((rest, field)) => {
+                    fields.extend(field);
+                    i = rest;
+                }
+                Err(_) => {
+                    // In lenient mode, a bare CR also ends a line.
+                    let end = match self.options.strict {
+                        true => i.find('\n'),
+                        false => i.find(['\n', '\r']).map(|n| match i[n..].starts_with("\r\n") {
+                            true => n + 1,
+                            false => n,
+                        }),
+                    }
+                    .map_or(i.len(), |n| n + 1);
+                    malformed.push(&i[..end]);
+                    i = &i[end..];
+                }
+            }
         }
+
+        (fields, malformed)
     }
 
-    pub fn parse<'a>(&'a self, text: &'a str) -> Result<Vec<Option<RawField<'a>>>, ParseError> {
-        let (_, msg) = self.body_parser(text)?;
-        Ok(msg)
+    /// Finds all places where the input relies on the lenient grammar, given the fields parsed from it.
+    ///
+    /// Returns the relaxation as a [`ParseError`] without a location, along with its byte range in the input. A byte order mark is not
+    /// reported, as it precedes the cleartext of a signed file.
+    pub fn relaxations(&self, text: &str, fields: &[RawField]) -> Vec<(ParseError, Range<usize>)> {
+        let mut relaxations = vec![];
+        if self.options.strict {
+            return relaxations;
+        }
+
+        for field in fields {
+            let start = text.offset(field.name) + field.name.len() + 1;
+            if text[start..].starts_with('\t') {
+                relaxations.push((ParseError::TabAfterSeparator { span: None }, start..start + 1));
+            }
+        }
+
+        for (start, _) in text.match_indices('\r') {
+            if !text[start + 1..].starts_with('\n') {
+                relaxations.push((ParseError::BareCarriageReturn { span: None }, start..start + 1));
+            }
+        }
+
+        if !text.is_empty() && !text.ends_with(['\n', '\r']) {
+            relaxations.push((
+                ParseError::MissingFinalLineEnding { span: None },
+                text.len()..text.len(),
+            ));
+        }
+
+        relaxations.sort_by_key(|(_, range)| range.start);
+        relaxations
     }
 
     // body             =  signed / unsigned
     // signed is handled separately.
-    fn body_parser<'a>(&'a self, i: &'a str) -> IResult<&'a str, Vec<Option<RawField<'a>>>> {
-        all_consuming(|x| self.unsigned_parser(x)).parse(i)
+    fn body_parser<'a>(&'a self, i: &'a str) -> IResult<&'a str, Vec<RawField<'a>>> {
+        match self.options.strict {
+            true => all_consuming(|x| self.unsigned_parser(x)).parse(i),
+            false => all_consuming(preceded(opt(char(BOM)), |x| self.unsigned_parser(x))).parse(i),
+        }
     }
 
     // unsigned       =  *line (contact-field eol) ; one or more required
@@ -41,12 +128,26 @@ impl SecurityTxtParser {
     //                   ; except that if contact-field appears more
     //                   ; than once, the order of those indicates
     //                   ; priority (see Section 3.5.3)
-    fn unsigned_parser<'a>(&'a self, i: &'a str) -> IResult<&'a str, Vec<Option<RawField<'a>>>> {
-        many1(|x| self.line_parser(x)).parse(i)
+    // Lines without a field are skipped while folding, so no intermediate collection of lines is built.
+    fn unsigned_parser<'a>(&'a self, i: &'a str) -> IResult<&'a str, Vec<RawField<'a>>> {
+        fold_many1(
+            |x| self.line_parser(x),
+            Vec::new,
+            |mut fields, field| {
+                fields.extend(field);
+                fields
+            },
+        )
+        .parse(i)
     }
 
     // line             =  [ (field / comment) ] eol
     fn line_parser<'a>(&'a self, i: &'a str) -> IResult<&'a str, Option<RawField<'a>>> {
+        // The lenient grammar accepts the end of input as line ending, which must not match an empty line.
+        if i.is_empty() {
+            return Err(nom::Err::Error(Error::new(i, ErrorKind::Eof)));
+        }
+
         let field_parser_opt = map(|x| self.field_parser(x), Some);
         let comment_parser_opt = map(|x| self.comment_parser(x), |_| None);
 
@@ -57,8 +158,12 @@ impl SecurityTxtParser {
     }
 
     // eol              =  *WSP [CR] LF
+    // The lenient grammar also accepts a bare CR, or the end of input.
     fn eol_parser<'a>(&'a self, i: &'a str) -> IResult<&'a str, &'a str> {
-        recognize((take_while(is_wsp), opt(|x| self.cr_parser(x)), |x| self.lf_parser(x))).parse(i)
+        match self.options.strict {
+            true => recognize((take_while(is_wsp), opt(|x| self.cr_parser(x)), |x| self.lf_parser(x))).parse(i),
+            false => recognize((take_while(is_wsp), alt((tag("\r\n"), tag("\n"), tag("\r"), eof)))).parse(i),
+        }
     }
 
     // field            =  ; optional fields
@@ -98,6 +203,7 @@ impl SecurityTxtParser {
     // uri              =  < URI as per Section 3 of [RFC3986] >
 
     // ext-field        =  field-name fs SP unstructured
+    // The lenient grammar also accepts HTAB instead of SP.
     fn ext_name_parser<'a>(&'a self, i: &'a str) -> IResult<&'a str, RawField<'a>> {
         let (i, (name, _, _, value)) = (
             |x| self.field_name_parser(x),
@@ -153,7 +259,10 @@ impl SecurityTxtParser {
 
     // SP               =  %x20
     fn sp_parser<'a>(&'a self, i: &'a str) -> IResult<&'a str, char> {
-        char(' ').parse(i)
+        match self.options.strict {
+            true => char(' ').parse(i),
+            false => satisfy(is_wsp).parse(i),
+        }
     }
 }
 
@@ -161,7 +270,7 @@ impl SecurityTxtParser {
 // ftext            =  %d33-57 /          ; Printable US-ASCII
 //                     %d59-126           ;  characters not including
 //                                        ;  ":".
-fn is_ftext_char(i: char) -> bool {
+pub(crate) fn is_ftext_char(i: char) -> bool {
     match i {
         '\x21'..='\x39' => true, // %d33-57
         '\x3B'..='\x7E' => true, // %d59-126
@@ -183,13 +292,13 @@ fn is_lf(i: char) -> bool {
 
 // VCHAR            =  %x21-7E
 //                       ; visible (printing) characters
-fn is_vchar(i: char) -> bool {
+pub(crate) fn is_vchar(i: char) -> bool {
     matches!(i, '\x21'..='\x7E')
 }
 
 // WSP              =  SP / HTAB
 //                       ; white space
-fn is_wsp(i: char) -> bool {
+pub(crate) fn is_wsp(i: char) -> bool {
     i == ' ' || i == '\t'
 }
 
@@ -212,7 +321,7 @@ mod tests {
             let file = path.unwrap().path();
             println!("Input file: {:?}", file);
             let buf = fs::read_to_string(file).unwrap();
-            let txt = unsigned_parser.parse(&buf);
+            let txt = unsigned_parser.parse(&buf, &SourceMap::new(&buf));
             assert!(txt.is_ok());
         }
     }
@@ -242,4 +351,90 @@ mod tests {
             assert_eq!(unsigned_parser.line_parser(input), Ok(("", result)));
         }
     }
+
+    #[test]
+    fn test_parse_recovering() {
+        let unsigned_parser = SecurityTxtParser::new(&Default::default());
+        let input = "foo: bar\ninvalid\n# comment\nbaz: qux\ntrailing";
+        let fields = vec![
+            RawField {
+                name: "foo",
+                value: "bar",
+            },
+            RawField {
+                name: "baz",
+                value: "qux",
+            },
+        ];
+
+        assert_eq!(
+            unsigned_parser.parse_recovering(input),
+            (fields, vec!["invalid\n", "trailing"])
+        );
+    }
+
+    #[test]
+    fn test_lenient_parser() {
+        let strict_parser = SecurityTxtParser::new(&SecurityTxtOptions::new(true));
+        let lenient_parser = SecurityTxtParser::new(&SecurityTxtOptions::new(false));
+        let input = "\u{FEFF}foo: bar\rbaz:\tqux\r\n# comment\rlast: line";
+        let fields = vec![
+            RawField {
+                name: "foo",
+                value: "bar",
+            },
+            RawField {
+                name: "baz",
+                value: "qux",
+            },
+            RawField {
+                name: "last",
+                value: "line",
+            },
+        ];
+
+        let source = SourceMap::new(input);
+        let error = ParseError::Malformed {
+            value: "\u{FEFF}foo: bar".to_owned(),
+            span: Some(source.span(0..11)),
+        };
+        assert_eq!(strict_parser.parse(input, &source), Err(error));
+        let parsed = lenient_parser.parse(input, &source).unwrap();
+        assert_eq!(parsed, fields);
+        assert_eq!(lenient_parser.parse_recovering(input), (fields, vec![]));
+
+        assert_eq!(
+            lenient_parser.relaxations(input, &parsed),
+            vec![
+                (ParseError::BareCarriageReturn { span: None }, 11..12),
+                (ParseError::TabAfterSeparator { span: None }, 16..17),
+                (ParseError::BareCarriageReturn { span: None }, 31..32),
+                (ParseError::MissingFinalLineEnding { span: None }, 42..42),
+            ]
+        );
+        assert_eq!(strict_parser.relaxations(input, &parsed), vec![]);
+    }
+
+    #[test]
+    fn test_lenient_parse_recovering() {
+        let lenient_parser = SecurityTxtParser::new(&SecurityTxtOptions::new(false));
+        let input = "foo: bar\rinvalid\rbaz: qux\r\ninvalid\r\n";
+
+        assert_eq!(
+            lenient_parser.parse_recovering(input),
+            (
+                vec![
+                    RawField {
+                        name: "foo",
+                        value: "bar",
+                    },
+                    RawField {
+                        name: "baz",
+                        value: "qux",
+                    },
+                ],
+                vec!["invalid\r", "invalid\r\n"]
+            )
+        );
+    }
 }
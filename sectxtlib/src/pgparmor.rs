@@ -0,0 +1,50 @@
+use super::signature_error::SignatureError;
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+/// Decodes the base64 data of an ASCII armor, including the optional CRC-24 checksum line.
+///
+/// The checksum is verified if present, as described in [RFC 4880, Section 6.1](https://www.rfc-editor.org/rfc/rfc4880#section-6.1).
+pub(crate) fn decode(data: &str) -> Result<Vec<u8>, SignatureError> {
+    let mut body = String::with_capacity(data.len());
+    let mut checksum = None;
+
+    for line in data.lines().map(str::trim_end) {
+        match line.strip_prefix('=') {
+            Some(crc) => checksum = Some(crc),
+            None => body.push_str(line),
+        }
+    }
+
+    let bytes = STANDARD.decode(body).map_err(|_| SignatureError::InvalidArmor)?;
+
+    if let Some(checksum) = checksum {
+        let checksum = STANDARD.decode(checksum).map_err(|_| SignatureError::InvalidArmor)?;
+        let expected = crc24::hash_raw(&bytes).to_be_bytes();
+
+        if checksum[..] != expected[1..] {
+            return Err(SignatureError::ChecksumMismatch);
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Encodes binary data as the body of an ASCII armor, including the CRC-24 checksum line.
+///
+/// Lines are wrapped after 64 characters and terminated by CRLF.
+#[cfg(feature = "openpgp")]
+pub(crate) fn encode(data: &[u8]) -> String {
+    let encoded = STANDARD.encode(data);
+    let checksum = crc24::hash_raw(data).to_be_bytes();
+    let mut out = String::with_capacity(encoded.len() + encoded.len() / 32 + 8);
+
+    for line in encoded.as_bytes().chunks(64) {
+        out.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        out.push_str("\r\n");
+    }
+
+    out.push('=');
+    out.push_str(&STANDARD.encode(&checksum[1..]));
+    out.push_str("\r\n");
+    out
+}
@@ -1,11 +1,12 @@
 use crate::SecurityTxtOptions;
 
 use super::parse_error::ParseError;
+use super::parsers::BOM;
 
 use nom::{
     branch::alt,
     bytes::complete::{tag, take_while, take_while1},
-    character::complete::{line_ending, none_of, one_of},
+    character::complete::{char, line_ending, none_of, one_of},
     combinator::{all_consuming, opt, peek, recognize},
     multi::{many0, many1, many1_count, separated_list1},
     sequence::{delimited, preceded, separated_pair, terminated},
@@ -22,10 +23,29 @@ pub(crate) struct PGPSignature<'a> {
 pub(crate) struct PGPCleartextMessage<'a> {
     pub hash_armor_headers: Vec<Vec<&'a str>>,
     pub cleartext: String,
+    pub cleartext_lines: Vec<&'a str>,
     pub signature: PGPSignature<'a>,
 }
 
-impl PGPCleartextMessage<'_> {}
+impl PGPCleartextMessage<'_> {
+    /// Returns the text covered by the signature, canonicalized as described in [RFC 4880, Section 7.1](https://www.rfc-editor.org/rfc/rfc4880#section-7.1)
+    #[cfg(feature = "openpgp")]
+    pub fn signed_text(&self) -> String {
+        canonical_text(self.cleartext_lines.iter().copied())
+    }
+}
+
+/// Canonicalizes the lines of a cleartext message for signing
+///
+/// Line endings and trailing whitespace are removed from every line, and lines are joined by CRLF. The line ending
+/// before the signature is not part of the signed text.
+#[cfg(feature = "openpgp")]
+pub(crate) fn canonical_text<'a>(lines: impl Iterator<Item = &'a str>) -> String {
+    lines
+        .map(|line| line.trim_end_matches(['\r', '\n']).trim_end_matches([' ', '\t']))
+        .collect::<Vec<&str>>()
+        .join("\r\n")
+}
 
 pub(crate) struct PGPCleartextMessageParser {
     options: SecurityTxtOptions,
@@ -39,15 +59,23 @@ impl PGPCleartextMessageParser {
     }
 
     pub fn parse<'a>(&'a self, text: &'a str) -> Result<PGPCleartextMessage<'a>, ParseError> {
-        let (_, msg) = self.signed_parser(text)?;
-        Ok(msg)
+        match self.signed_parser(text) {
+            Ok((_, msg)) => Ok(msg),
+            Err(err) => Err(ParseError::malformed_signed_message(text, &err)),
+        }
+    }
+
+    /// Returns whether the text starts with the cleartext header, i.e., whether it has to be parsed as a signed message
+    ///
+    /// Only the header is inspected, so this is cheap regardless of the size of the text.
+    pub fn is_signed(&self, text: &str) -> bool {
+        (|x| self.bom_parser(x), |x| self.cleartext_header_parser(x))
+            .parse(text)
+            .is_ok()
     }
 
     fn lf_parser<'a>(&'a self, i: &'a str) -> IResult<&'a str, &'a str> {
-        match self.options.strict {
-            true => line_ending.parse(i),
-            false => tag("\n").parse(i),
-        }
+        line_ending.parse(i)
     }
 
     // signed           =  cleartext-header
@@ -56,7 +84,8 @@ impl PGPCleartextMessageParser {
     //                     cleartext
     //                     signature
     fn signed_parser<'a>(&'a self, i: &'a str) -> IResult<&'a str, PGPCleartextMessage<'a>> {
-        let (_, (_, hash_armor_headers, _, cleartext, signature)) = all_consuming((
+        let (_, (_, _, hash_armor_headers, _, cleartext_lines, signature)) = all_consuming((
+            |x| self.bom_parser(x),
             |x| self.cleartext_header_parser(x),
             many1(|x| self.hash_header_parser(x)),
             |x| self.lf_parser(x),
@@ -69,12 +98,21 @@ impl PGPCleartextMessageParser {
             i,
             PGPCleartextMessage {
                 hash_armor_headers,
-                cleartext,
+                cleartext: cleartext_lines.concat(),
+                cleartext_lines,
                 signature,
             },
         ))
     }
 
+    // The lenient grammar accepts a byte order mark at the start of the file.
+    fn bom_parser<'a>(&'a self, i: &'a str) -> IResult<&'a str, Option<char>> {
+        match self.options.strict {
+            true => Ok((i, None)),
+            false => opt(char(BOM)).parse(i),
+        }
+    }
+
     // cleartext-header =  %s"-----BEGIN PGP SIGNED MESSAGE-----" CRLF
     fn cleartext_header_parser<'a>(&'a self, i: &'a str) -> IResult<&'a str, &'a str> {
         terminated(tag("-----BEGIN PGP SIGNED MESSAGE-----"), |x| self.lf_parser(x)).parse(i)
@@ -107,9 +145,8 @@ impl PGPCleartextMessageParser {
 
     // cleartext        =  *((line-dash / line-from / line-nodash) [CR] LF)
     // EOL is handled in branches.
-    fn cleartext_parser<'a>(&'a self, i: &'a str) -> IResult<&'a str, String> {
-        let (i, lines) = many0(alt((|x| self.line_dash_parser(x), |x| self.line_nodash_parser(x)))).parse(i)?;
-        Ok((i, lines.join("")))
+    fn cleartext_parser<'a>(&'a self, i: &'a str) -> IResult<&'a str, Vec<&'a str>> {
+        many0(alt((|x| self.line_dash_parser(x), |x| self.line_nodash_parser(x)))).parse(i)
     }
 
     // line-dash        =  ("- ") "-" *UTF8-char-not-cr
@@ -239,6 +276,7 @@ Test\r
         let msg = PGPCleartextMessage {
             hash_armor_headers: vec![vec!["SHA512"]],
             cleartext: "Test\r\nTest\r\n".into(),
+            cleartext_lines: vec!["Test\r\n", "Test\r\n"],
             signature: PGPSignature {
                 signature: SIGNATURE_DATA,
                 keys: vec![],
@@ -247,6 +285,34 @@ Test\r
         assert_eq!(signed_parser.parse(&txt), Ok(msg));
     }
 
+    #[cfg(feature = "openpgp")]
+    #[test]
+    fn test_signed_text() {
+        let signed_parser = PGPCleartextMessageParser::new(&Default::default());
+        let txt = format!(
+            "-----BEGIN PGP SIGNED MESSAGE-----\r\nHash: SHA512\r\n\r\nTest \t\r\n- -Test\n\r\n-----BEGIN PGP SIGNATURE-----\r\n\r\n{SIGNATURE_DATA}-----END PGP SIGNATURE-----\r\n"
+        );
+        let msg = signed_parser.parse(&txt).unwrap();
+        assert_eq!(msg.signed_text(), "Test\r\n-Test\r\n");
+    }
+
+    #[test]
+    fn test_is_signed() {
+        let strict_parser = PGPCleartextMessageParser::new(&SecurityTxtOptions::new(true));
+        let lenient_parser = PGPCleartextMessageParser::new(&SecurityTxtOptions::new(false));
+        let header = "-----BEGIN PGP SIGNED MESSAGE-----\r\nHash: SHA512\r\n";
+        let with_bom = format!("{BOM}{header}");
+
+        assert!(strict_parser.is_signed(header));
+        assert!(strict_parser.is_signed("-----BEGIN PGP SIGNED MESSAGE-----\n"));
+        assert!(!strict_parser.is_signed(&with_bom));
+        assert!(lenient_parser.is_signed(&with_bom));
+        assert!(!lenient_parser.is_signed("-----BEGIN PGP SIGNED MESSAGE-----"));
+        assert!(!lenient_parser.is_signed("Contact: mailto:security@example.com\n"));
+        assert!(!lenient_parser.is_signed("# -----BEGIN PGP SIGNED MESSAGE-----\n"));
+        assert!(!lenient_parser.is_signed(""));
+    }
+
     #[test]
     fn test_hash_header_parser() {
         let signed_parser = PGPCleartextMessageParser::new(&Default::default());
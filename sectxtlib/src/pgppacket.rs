@@ -0,0 +1,265 @@
+use super::signature_error::SignatureError;
+use std::fmt;
+
+/// A [hash algorithm](https://www.rfc-editor.org/rfc/rfc4880#section-9.4) as used in OpenPGP
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    Ripemd160,
+    Sha256,
+    Sha384,
+    Sha512,
+    Sha224,
+    /// An algorithm not known to this library, identified by its ID
+    Unknown(u8),
+}
+
+impl From<u8> for HashAlgorithm {
+    fn from(id: u8) -> Self {
+        match id {
+            1 => Self::Md5,
+            2 => Self::Sha1,
+            3 => Self::Ripemd160,
+            8 => Self::Sha256,
+            9 => Self::Sha384,
+            10 => Self::Sha512,
+            11 => Self::Sha224,
+            _ => Self::Unknown(id),
+        }
+    }
+}
+
+impl HashAlgorithm {
+    /// Returns whether the algorithm is no longer collision resistant, so that signatures using it can be forged
+    pub fn is_insecure(&self) -> bool {
+        matches!(self, Self::Md5 | Self::Sha1 | Self::Ripemd160)
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Md5 => write!(f, "MD5"),
+            Self::Sha1 => write!(f, "SHA1"),
+            Self::Ripemd160 => write!(f, "RIPEMD160"),
+            Self::Sha256 => write!(f, "SHA256"),
+            Self::Sha384 => write!(f, "SHA384"),
+            Self::Sha512 => write!(f, "SHA512"),
+            Self::Sha224 => write!(f, "SHA224"),
+            Self::Unknown(id) => write!(f, "unknown ({id})"),
+        }
+    }
+}
+
+/// A [public-key algorithm](https://www.rfc-editor.org/rfc/rfc4880#section-9.1) as used in OpenPGP
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PublicKeyAlgorithm {
+    Rsa,
+    Dsa,
+    Ecdh,
+    Ecdsa,
+    EdDsa,
+    Ed25519,
+    /// An algorithm not known to this library, identified by its ID
+    Unknown(u8),
+}
+
+impl From<u8> for PublicKeyAlgorithm {
+    fn from(id: u8) -> Self {
+        match id {
+            1..=3 => Self::Rsa,
+            17 => Self::Dsa,
+            18 => Self::Ecdh,
+            19 => Self::Ecdsa,
+            22 => Self::EdDsa,
+            27 => Self::Ed25519,
+            _ => Self::Unknown(id),
+        }
+    }
+}
+
+pub(crate) const TAG_SIGNATURE: u8 = 2;
+
+pub(crate) const SUBPACKET_ISSUER: u8 = 16;
+pub(crate) const SUBPACKET_ISSUER_FINGERPRINT: u8 = 33;
+
+/// A cursor over the bytes of a packet
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn take(&mut self, n: usize) -> Result<&'a [u8], SignatureError> {
+        if self.data.len() < n {
+            return Err(SignatureError::InvalidPacket);
+        }
+
+        let (taken, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, SignatureError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16, SignatureError> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, SignatureError> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+}
+
+/// An OpenPGP packet as described in [RFC 4880, Section 4](https://www.rfc-editor.org/rfc/rfc4880#section-4)
+#[derive(Debug, PartialEq)]
+pub(crate) struct Packet<'a> {
+    pub tag: u8,
+    pub body: &'a [u8],
+}
+
+/// Splits a sequence of packets, supporting both the old and the new packet format.
+///
+/// Partial body lengths are not supported, as they are not used for signatures or keys.
+pub(crate) fn parse_packets(data: &[u8]) -> Result<Vec<Packet<'_>>, SignatureError> {
+    let mut reader = Reader::new(data);
+    let mut packets = vec![];
+
+    while !reader.is_empty() {
+        let ctb = reader.u8()?;
+        if ctb & 0x80 == 0 {
+            return Err(SignatureError::InvalidPacket);
+        }
+
+        let (tag, len) = match ctb & 0x40 {
+            0 => {
+                let tag = (ctb >> 2) & 0x0F;
+                let len = match ctb & 0x03 {
+                    0 => reader.u8()? as usize,
+                    1 => reader.u16()? as usize,
+                    2 => reader.u32()? as usize,
+                    _ => reader.data.len(),
+                };
+                (tag, len)
+            }
+            _ => {
+                let tag = ctb & 0x3F;
+                let len = match reader.u8()? {
+                    o @ 0..=191 => o as usize,
+                    o @ 192..=223 => ((o as usize - 192) << 8) + reader.u8()? as usize + 192,
+                    255 => reader.u32()? as usize,
+                    _ => return Err(SignatureError::InvalidPacket),
+                };
+                (tag, len)
+            }
+        };
+
+        packets.push(Packet {
+            tag,
+            body: reader.take(len)?,
+        });
+    }
+
+    Ok(packets)
+}
+
+/// A version 4 signature packet as described in [RFC 4880, Section 5.2.3](https://www.rfc-editor.org/rfc/rfc4880#section-5.2.3)
+#[derive(Debug, PartialEq)]
+pub(crate) struct SignaturePacket<'a> {
+    pub public_key_algorithm: PublicKeyAlgorithm,
+    pub hash_algorithm: HashAlgorithm,
+    pub hashed_subpackets: Vec<(u8, &'a [u8])>,
+    pub unhashed_subpackets: Vec<(u8, &'a [u8])>,
+}
+
+impl<'a> SignaturePacket<'a> {
+    pub fn parse(body: &'a [u8]) -> Result<Self, SignatureError> {
+        let mut reader = Reader::new(body);
+
+        if reader.u8()? != 4 {
+            return Err(SignatureError::UnsupportedVersion);
+        }
+
+        reader.take(1)?; // The signature type
+        let public_key_algorithm = reader.u8()?.into();
+        let hash_algorithm = reader.u8()?.into();
+        let hashed_len = reader.u16()? as usize;
+        let hashed_subpackets = parse_subpackets(reader.take(hashed_len)?)?;
+        let unhashed_len = reader.u16()? as usize;
+        let unhashed_subpackets = parse_subpackets(reader.take(unhashed_len)?)?;
+
+        Ok(Self {
+            public_key_algorithm,
+            hash_algorithm,
+            hashed_subpackets,
+            unhashed_subpackets,
+        })
+    }
+
+    fn subpacket(&self, kind: u8) -> Option<&'a [u8]> {
+        self.hashed_subpackets
+            .iter()
+            .chain(self.unhashed_subpackets.iter())
+            .find(|(t, _)| *t == kind)
+            .map(|(_, data)| *data)
+    }
+
+    /// Returns the fingerprint of the issuing key, if specified
+    pub fn issuer_fingerprint(&self) -> Option<&'a [u8]> {
+        match self.subpacket(SUBPACKET_ISSUER_FINGERPRINT)? {
+            [4, fingerprint @ ..] if fingerprint.len() == 20 => Some(fingerprint),
+            _ => None,
+        }
+    }
+
+    /// Returns the key ID of the issuing key, if specified
+    pub fn issuer_key_id(&self) -> Option<&'a [u8]> {
+        match self.subpacket(SUBPACKET_ISSUER) {
+            Some(key_id) if key_id.len() == 8 => Some(key_id),
+            _ => self.issuer_fingerprint().map(|fpr| &fpr[12..]),
+        }
+    }
+}
+
+/// Splits signature subpackets as described in [RFC 4880, Section 5.2.3.1](https://www.rfc-editor.org/rfc/rfc4880#section-5.2.3.1)
+///
+/// The critical bit is stripped from the subpacket type.
+fn parse_subpackets(data: &[u8]) -> Result<Vec<(u8, &[u8])>, SignatureError> {
+    let mut reader = Reader::new(data);
+    let mut subpackets = vec![];
+
+    while !reader.is_empty() {
+        let len = match reader.u8()? {
+            o @ 0..=191 => o as usize,
+            o @ 192..=254 => ((o as usize - 192) << 8) + reader.u8()? as usize + 192,
+            255 => reader.u32()? as usize,
+        };
+        if len == 0 {
+            return Err(SignatureError::InvalidPacket);
+        }
+
+        let body = reader.take(len)?;
+        subpackets.push((body[0] & 0x7F, &body[1..]));
+    }
+
+    Ok(subpackets)
+}
+
+/// Formats binary data such as fingerprints as uppercase hex
+pub(crate) fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{b:02X}")).collect()
+}
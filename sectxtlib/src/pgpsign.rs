@@ -0,0 +1,162 @@
+use super::pgparmor;
+use super::pgpcleartextmessage::canonical_text;
+use super::render::RenderOptions;
+use super::securitytxt::SecurityTxt;
+use super::signature_error::SignatureError;
+use pgp::composed::{Deserializable, SignedPublicKey, SignedSecretKey};
+use pgp::crypto::hash::HashAlgorithm;
+use pgp::packet::{PacketTrait, SignatureConfig, SignatureType, Subpacket, SubpacketData};
+use pgp::types::{KeyDetails, Password, Timestamp};
+use std::fmt;
+
+/// The hash algorithm used for creating signatures
+const HASH_ALGORITHM: HashAlgorithm = HashAlgorithm::Sha512;
+
+/// An OpenPGP secret key for signing security.txt files
+///
+/// Keys are parsed and signatures are created by [rPGP](https://github.com/rpgp/rpgp). The primary key is used if it
+/// supports signing, and the first subkey flagged for signing otherwise. The secret key material must not be
+/// protected by a passphrase.
+#[derive(Clone)]
+pub struct SigningKey {
+    key: SignedSecretKey,
+    subkey: Option<usize>,
+}
+
+impl SigningKey {
+    /// Loads the signing key from an ASCII-armored private key block
+    pub fn from_armored(text: &str) -> Result<Self, SignatureError> {
+        match SignedSecretKey::from_string(text) {
+            Ok((key, _)) => Self::new(key),
+            Err(_) if SignedPublicKey::from_string(text).is_ok() => Err(SignatureError::SecretKeyMissing),
+            Err(_) => Err(SignatureError::InvalidArmor),
+        }
+    }
+
+    /// Loads the signing key from a sequence of binary OpenPGP packets
+    pub fn from_bytes(data: &[u8]) -> Result<Self, SignatureError> {
+        match SignedSecretKey::from_bytes(data) {
+            Ok(key) => Self::new(key),
+            Err(_) if SignedPublicKey::from_bytes(data).is_ok() => Err(SignatureError::SecretKeyMissing),
+            Err(_) => Err(SignatureError::InvalidPacket),
+        }
+    }
+
+    fn new(key: SignedSecretKey) -> Result<Self, SignatureError> {
+        key.verify_bindings().map_err(|_| SignatureError::InvalidPacket)?;
+
+        let subkey = match key.primary_key.algorithm().can_sign() {
+            true => None,
+            false => {
+                let index = key
+                    .secret_subkeys
+                    .iter()
+                    .position(|subkey| {
+                        subkey.key.algorithm().can_sign() && subkey.signatures.iter().any(|s| s.key_flags().sign())
+                    })
+                    .ok_or(SignatureError::SecretKeyMissing)?;
+                Some(index)
+            }
+        };
+
+        let encrypted = match subkey {
+            Some(index) => key.secret_subkeys[index].key.secret_params().is_encrypted(),
+            None => key.primary_key.secret_params().is_encrypted(),
+        };
+        if encrypted {
+            return Err(SignatureError::EncryptedKey);
+        }
+
+        Ok(Self { key, subkey })
+    }
+
+    /// Returns the fingerprint of the key used for signing as uppercase hex
+    pub fn fingerprint(&self) -> String {
+        match self.subkey {
+            Some(index) => format!("{:X}", self.key.secret_subkeys[index].key.fingerprint()),
+            None => format!("{:X}", self.key.primary_key.fingerprint()),
+        }
+    }
+
+    /// Creates a cleartext signed message from the given text, as described in [RFC 4880, Section 7](https://www.rfc-editor.org/rfc/rfc4880#section-7).
+    ///
+    /// Lines starting with a dash are dash-escaped, and all lines are terminated by CRLF. The signature is made over
+    /// the canonicalized text using SHA512.
+    pub fn sign(&self, text: &str) -> Result<String, SignatureError> {
+        let signature = self.signature_packet(&canonical_text(text.lines()))?;
+
+        let mut out = String::from("-----BEGIN PGP SIGNED MESSAGE-----\r\n");
+        out.push_str(&format!("Hash: {HASH_ALGORITHM}\r\n\r\n"));
+
+        for line in text.lines() {
+            if line.starts_with('-') {
+                out.push_str("- ");
+            }
+            out.push_str(line);
+            out.push_str("\r\n");
+        }
+
+        out.push_str("-----BEGIN PGP SIGNATURE-----\r\n\r\n");
+        out.push_str(&pgparmor::encode(&signature));
+        out.push_str("-----END PGP SIGNATURE-----\r\n");
+
+        Ok(out)
+    }
+
+    // A version 4 signature packet including its header, which names the issuer in both of the usual ways
+    fn signature_packet(&self, text: &str) -> Result<Vec<u8>, SignatureError> {
+        let signer: &dyn pgp::types::SigningKey = match self.subkey {
+            Some(index) => &self.key.secret_subkeys[index].key,
+            None => &self.key.primary_key,
+        };
+
+        let subpackets = || -> pgp::errors::Result<_> {
+            Ok((
+                vec![
+                    Subpacket::regular(SubpacketData::SignatureCreationTime(Timestamp::now()))?,
+                    Subpacket::regular(SubpacketData::IssuerFingerprint(signer.fingerprint()))?,
+                ],
+                vec![Subpacket::regular(SubpacketData::IssuerKeyId(signer.legacy_key_id()))?],
+            ))
+        };
+        let (hashed, unhashed) = subpackets().map_err(|_| SignatureError::InvalidPacket)?;
+
+        let mut config = SignatureConfig::v4(SignatureType::Text, signer.algorithm(), HASH_ALGORITHM);
+        config.hashed_subpackets = hashed;
+        config.unhashed_subpackets = unhashed;
+
+        let signature = config
+            .sign(&Box::new(signer), &Password::empty(), text.as_bytes())
+            .map_err(|_| SignatureError::UnsupportedAlgorithm)?;
+
+        let mut packet = vec![];
+        signature
+            .to_writer_with_header(&mut packet)
+            .map_err(|_| SignatureError::InvalidPacket)?;
+
+        Ok(packet)
+    }
+}
+
+impl SecurityTxt {
+    /// Renders the security.txt file and signs it with the given key.
+    ///
+    /// The result is a cleartext signed message which can be parsed again using [`SecurityTxt::parse`].
+    pub fn sign(&self, key: &SigningKey) -> Result<String, SignatureError> {
+        self.sign_with(key, &Default::default())
+    }
+
+    /// Renders the security.txt file with the given options and signs it with the given key
+    pub fn sign_with(&self, key: &SigningKey, options: &RenderOptions) -> Result<String, SignatureError> {
+        key.sign(&self.render_with(options))
+    }
+}
+
+// The secret key material is deliberately left out.
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningKey")
+            .field("fingerprint", &self.fingerprint())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,236 @@
+use super::pgparmor;
+use super::pgpcleartextmessage::PGPCleartextMessageParser;
+use super::pgppacket::{to_hex, HashAlgorithm};
+use super::securitytxt_options::SecurityTxtOptions;
+use super::signature_error::SignatureError;
+use pgp::composed::{Deserializable, DetachedSignature, SignedPublicKey};
+use pgp::packet::{PublicKey, PublicSubkey, Signature, SignatureType};
+use pgp::types::KeyDetails;
+
+/// The outcome of verifying a signature against a [`Keyring`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SignatureValidity {
+    /// The signature was made by a key in the keyring and matches the signed text
+    Valid,
+
+    /// The signature was made by a key in the keyring, but does not match the signed text
+    Invalid,
+
+    /// The signature was not made by any key in the keyring
+    UnknownKey,
+}
+
+/// The result of verifying the signature of a security.txt file
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignatureVerification {
+    /// The fingerprint of the signing key as uppercase hex, if known
+    pub fingerprint: Option<String>,
+
+    /// The key ID of the signing key as uppercase hex, if known
+    pub key_id: Option<String>,
+
+    /// The hash algorithm used for the signature
+    pub hash_algorithm: HashAlgorithm,
+
+    /// Whether the signature is valid
+    pub validity: SignatureValidity,
+}
+
+/// A collection of OpenPGP public keys to verify signatures of security.txt files against
+///
+/// Keys are parsed and signatures are checked by [rPGP](https://github.com/rpgp/rpgp). Signatures may be made by a
+/// primary key or one of its subkeys. Signatures using MD5, SHA1, or RIPEMD160 are rejected unless allowed by
+/// [`Keyring::allow_insecure_hashes`].
+#[derive(Clone, Debug, Default)]
+pub struct Keyring {
+    keys: Vec<SignedPublicKey>,
+    allow_insecure_hashes: bool,
+}
+
+impl Keyring {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Loads all keys from an ASCII-armored public key block
+    pub fn from_armored(text: &str) -> Result<Self, SignatureError> {
+        let mut keyring = Self::new();
+        keyring.add_armored(text)?;
+        Ok(keyring)
+    }
+
+    /// Sets whether to accept signatures using hash algorithms which are no longer collision resistant
+    pub fn allow_insecure_hashes(mut self, allow: bool) -> Self {
+        self.allow_insecure_hashes = allow;
+        self
+    }
+
+    /// Adds all keys from an ASCII-armored public key block
+    pub fn add_armored(&mut self, text: &str) -> Result<(), SignatureError> {
+        let (keys, _) = SignedPublicKey::from_string_many(text).map_err(|_| SignatureError::InvalidArmor)?;
+        self.add_keys(keys)
+    }
+
+    /// Adds all keys from a sequence of binary OpenPGP packets
+    pub fn add_bytes(&mut self, data: &[u8]) -> Result<(), SignatureError> {
+        let keys = SignedPublicKey::from_bytes_many(data).map_err(|_| SignatureError::InvalidPacket)?;
+        self.add_keys(keys)
+    }
+
+    // Keys whose subkey bindings cannot be verified are rejected, as their subkeys may not belong to them.
+    fn add_keys(
+        &mut self,
+        keys: impl Iterator<Item = pgp::errors::Result<SignedPublicKey>>,
+    ) -> Result<(), SignatureError> {
+        for key in keys {
+            let key = key.map_err(|_| SignatureError::InvalidPacket)?;
+            key.verify_bindings().map_err(|_| SignatureError::InvalidPacket)?;
+            self.keys.push(key);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of keys in the keyring
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns whether the keyring contains no keys
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the fingerprints of the primary keys in the keyring as uppercase hex
+    pub fn fingerprints(&self) -> impl Iterator<Item = String> + '_ {
+        self.keys.iter().map(|k| format!("{:X}", k.primary_key.fingerprint()))
+    }
+
+    /// Verifies the signature of a signed security.txt file.
+    ///
+    /// The signed text is canonicalized according to [RFC 4880, Section 7.1](https://www.rfc-editor.org/rfc/rfc4880#section-7.1),
+    /// i.e., dash-escaping is removed, trailing whitespace is stripped, and line endings are normalized to CRLF. If
+    /// the message declares its hash algorithms in "Hash" armor headers, the signature must use one of them.
+    pub fn verify(&self, text: &str, options: &SecurityTxtOptions) -> Result<SignatureVerification, SignatureError> {
+        let signed_parser = PGPCleartextMessageParser::new(options);
+        let msg = match signed_parser.parse(text) {
+            Ok(msg) => msg,
+            Err(_) if signed_parser.is_signed(text) => return Err(SignatureError::InvalidArmor),
+            Err(_) => return Err(SignatureError::NotSigned),
+        };
+
+        let data = pgparmor::decode(msg.signature.signature)?;
+        let signatures = DetachedSignature::from_bytes_many(&data[..])
+            .and_then(|signatures| {
+                signatures
+                    .map(|s| s.map(|s| s.signature))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|_| SignatureError::InvalidPacket)?;
+
+        // The first signature made by a known key is verified, so that further signatures by others do not matter.
+        let signature = signatures
+            .iter()
+            .find(|s| self.find(s).is_some())
+            .or(signatures.first())
+            .ok_or(SignatureError::InvalidPacket)?;
+
+        if signature.typ() != Some(SignatureType::Text) {
+            return Err(SignatureError::UnsupportedSignatureType);
+        }
+
+        let hash_algorithm = signature
+            .hash_alg()
+            .map(|alg| HashAlgorithm::from(u8::from(alg)))
+            .ok_or(SignatureError::UnsupportedVersion)?;
+
+        let mut declared = msg.hash_armor_headers.iter().flatten().peekable();
+        if declared.peek().is_some() && !declared.any(|h| h.eq_ignore_ascii_case(&hash_algorithm.to_string())) {
+            return Err(SignatureError::HashHeaderMismatch(hash_algorithm));
+        }
+        if hash_algorithm.is_insecure() && !self.allow_insecure_hashes {
+            return Err(SignatureError::InsecureHashAlgorithm(hash_algorithm));
+        }
+
+        let signed_text = msg.signed_text();
+        let (fingerprint, validity) = match self.find(signature) {
+            Some(key) => {
+                let validity = match key.verify(signature, signed_text.as_bytes()) {
+                    true => SignatureValidity::Valid,
+                    false => SignatureValidity::Invalid,
+                };
+                (Some(key.fingerprint()), validity)
+            }
+            None => {
+                let fingerprint = signature.issuer_fingerprint().first().map(|f| format!("{f:X}"));
+                (fingerprint, SignatureValidity::UnknownKey)
+            }
+        };
+
+        // Version 4 key IDs are the last eight octets of the fingerprint, so they can be given even if not specified.
+        let key_id = signature
+            .issuer_key_id()
+            .first()
+            .map(|id| to_hex(id.as_ref()))
+            .or_else(|| {
+                let fingerprint = signature.issuer_fingerprint().into_iter().next()?;
+                let fingerprint = fingerprint.as_bytes();
+                match fingerprint.len() {
+                    20 => Some(to_hex(&fingerprint[12..])),
+                    _ => None,
+                }
+            });
+
+        Ok(SignatureVerification {
+            fingerprint,
+            key_id,
+            hash_algorithm,
+            validity,
+        })
+    }
+
+    /// Returns the key which issued the signature, which is either a primary key or one of its subkeys
+    fn find(&self, signature: &Signature) -> Option<Issuer<'_>> {
+        let fingerprints = signature.issuer_fingerprint();
+        let key_ids = signature.issuer_key_id();
+        let is_issuer = |key: &dyn KeyDetails| match fingerprints.is_empty() {
+            false => fingerprints.iter().any(|f| **f == key.fingerprint()),
+            true => key_ids.iter().any(|id| **id == key.legacy_key_id()),
+        };
+
+        self.keys.iter().find_map(|key| {
+            if is_issuer(&key.primary_key) {
+                return Some(Issuer::Primary(&key.primary_key));
+            }
+
+            key.public_subkeys
+                .iter()
+                .find(|subkey| is_issuer(&subkey.key))
+                .map(|subkey| Issuer::Subkey(&subkey.key))
+        })
+    }
+}
+
+/// A key which issued a signature
+enum Issuer<'a> {
+    Primary(&'a PublicKey),
+    Subkey(&'a PublicSubkey),
+}
+
+impl Issuer<'_> {
+    fn fingerprint(&self) -> String {
+        match self {
+            Self::Primary(key) => format!("{:X}", key.fingerprint()),
+            Self::Subkey(key) => format!("{:X}", key.fingerprint()),
+        }
+    }
+
+    fn verify(&self, signature: &Signature, data: &[u8]) -> bool {
+        match self {
+            Self::Primary(key) => signature.verify(key, data).is_ok(),
+            Self::Subkey(key) => signature.verify(key, data).is_ok(),
+        }
+    }
+}
@@ -1,5 +1,15 @@
+use nom::Offset;
+use std::ops::Range;
+
 #[derive(Debug, PartialEq)]
 pub(crate) struct RawField<'a> {
     pub name: &'a str,
     pub value: &'a str,
 }
+
+impl RawField<'_> {
+    /// Returns the byte range of the field within the parsed text
+    pub fn range(&self, text: &str) -> Range<usize> {
+        text.offset(self.name)..text.offset(self.value) + self.value.len()
+    }
+}
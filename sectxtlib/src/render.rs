@@ -0,0 +1,85 @@
+use super::securitytxt::SecurityTxt;
+use chrono::SecondsFormat;
+use std::fmt;
+
+/// Options for rendering a security.txt file
+#[derive(Clone, Debug, Default)]
+pub struct RenderOptions {
+    /// Comments to place at the top of the file, one per line
+    pub comments: Vec<String>,
+}
+
+impl RenderOptions {
+    pub fn new(comments: Vec<String>) -> Self {
+        Self { comments }
+    }
+}
+
+fn write_line(f: &mut impl fmt::Write, name: &str, value: &str) -> fmt::Result {
+    write!(f, "{name}: {value}\r\n")
+}
+
+impl SecurityTxt {
+    /// Renders the security.txt file according to [RFC 9116](https://www.rfc-editor.org/rfc/rfc9116).
+    ///
+    /// Fields are written in a fixed order, and lines are terminated by CRLF. "Contact" fields retain their order, as
+    /// it indicates their priority. The signature of a signed file is not rendered, so the output is always unsigned.
+    pub fn render_with(&self, options: &RenderOptions) -> String {
+        let mut out = String::new();
+        self.write_to(&mut out, options)
+            .expect("writing to a String cannot fail");
+        out
+    }
+
+    fn write_to(&self, f: &mut impl fmt::Write, options: &RenderOptions) -> fmt::Result {
+        for comment in &options.comments {
+            for line in comment.lines() {
+                match line.is_empty() {
+                    true => write!(f, "#\r\n")?,
+                    false => write!(f, "# {line}\r\n")?,
+                }
+            }
+        }
+
+        for field in &self.contact {
+            write_line(f, "Contact", field.uri.as_str())?;
+        }
+        write_line(
+            f,
+            "Expires",
+            &self.expires.datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        )?;
+        for field in &self.encryption {
+            write_line(f, "Encryption", field.uri.as_str())?;
+        }
+        for field in &self.acknowledgments {
+            write_line(f, "Acknowledgments", field.uri.as_str())?;
+        }
+        if let Some(field) = &self.preferred_languages {
+            write_line(f, "Preferred-Languages", &field.languages.join(", "))?;
+        }
+        for field in &self.canonical {
+            write_line(f, "Canonical", field.uri.as_str())?;
+        }
+        for field in &self.policy {
+            write_line(f, "Policy", field.uri.as_str())?;
+        }
+        for field in &self.hiring {
+            write_line(f, "Hiring", field.uri.as_str())?;
+        }
+        for field in &self.csaf {
+            write_line(f, "CSAF", field.uri.as_str())?;
+        }
+        for field in &self.extension {
+            write_line(f, &field.name, &field.value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for SecurityTxt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f, &Default::default())
+    }
+}
@@ -1,6 +1,8 @@
-use crate::parsers::SecurityTxtParser;
+use crate::input;
+use crate::parsers::{SecurityTxtParser, BOM};
 use crate::pgpcleartextmessage::PGPCleartextMessageParser;
 
+use super::diagnostics::{Diagnostics, Finding};
 use super::fields::{
     AcknowledgmentsField, CanonicalField, ContactField, CsafField, EncryptionField, ExpiresField, ExtensionField,
     HiringField, PolicyField, PreferredLanguagesField,
@@ -8,12 +10,21 @@ use super::fields::{
 use super::parse_error::ParseError;
 use super::raw_field::RawField;
 use super::securitytxt_options::SecurityTxtOptions;
+use super::securitytxt_partial::PartialSecurityTxt;
+use super::signature_info::SignatureInfo;
+use super::span::SourceMap;
+use std::borrow::Cow;
 use std::cmp::Ordering;
+use std::io::Read;
 use std::str::FromStr;
+use url::Url;
 use valuable::Valuable;
 
 /// A representation of an [RFC 9116](https://www.rfc-editor.org/rfc/rfc9116) security.txt file
+///
+/// With the `serde` feature, the type can be serialized and deserialized. See the crate README for the JSON schema.
 #[derive(Debug, PartialEq, Valuable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SecurityTxt {
     /// A collection of "Acknowledgments" fields
     pub acknowledgments: Vec<AcknowledgmentsField>,
@@ -44,106 +55,214 @@ pub struct SecurityTxt {
 
     /// The "Preferred-Languages" field, if available
     pub preferred_languages: Option<PreferredLanguagesField>,
+
+    /// Metadata about the OpenPGP signature, if the file was signed
+    pub signature: Option<SignatureInfo>,
 }
 
 impl SecurityTxt {
-    fn validate_contact_fields(fields: &[ContactField]) -> Result<(), ParseError> {
-        if fields.is_empty() {
-            return Err(ParseError::ContactFieldMissing);
-        }
+    pub(crate) fn new(
+        fields: Vec<RawField>,
+        text: &str,
+        source: &SourceMap,
+        signature: Option<SignatureInfo>,
+        options: &SecurityTxtOptions,
+    ) -> Result<Self, ParseError> {
+        let mut findings = vec![];
+        let locate = |field: &RawField| Some(source.span(field.range(text)));
+        let mut partial = PartialSecurityTxt::new(fields, locate, options, &mut findings);
+        partial.signature = signature;
 
-        Ok(())
+        Diagnostics { findings, partial }.into_result()
     }
 
-    fn validate_expires(fields: &[ExpiresField]) -> Result<(), ParseError> {
-        if fields.is_empty() {
-            return Err(ParseError::ExpiresFieldMissing);
-        }
-        if fields.len() > 1 {
-            return Err(ParseError::ExpiresFieldMultiple);
-        }
+    /// Parses a security.txt file as a string according to [RFC 9116](https://www.rfc-editor.org/rfc/rfc9116).
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let options = Default::default();
+        Self::parse_with(text, &options)
+    }
 
-        Ok(())
+    /// Parses a security.txt file as a string according to [RFC 9116](https://www.rfc-editor.org/rfc/rfc9116).
+    pub fn parse_with(text: &str, options: &SecurityTxtOptions) -> Result<Self, ParseError> {
+        Self::parse_fields(text, options, |fields, cleartext, source, signature| {
+            Self::new(fields, cleartext, source, signature, options)
+        })
     }
 
-    fn validate_preferred_languages(fields: &[PreferredLanguagesField]) -> Result<(), ParseError> {
-        if fields.len() > 1 {
-            return Err(ParseError::PreferredLanguagesFieldMultiple);
+    /// Parses the fields of a signed or unsigned file, and passes them to `f`.
+    ///
+    /// Besides the fields, `f` receives the text they were parsed from, which is the cleartext for a signed file, the
+    /// mapping from that text to the original input, and the signature if the file was signed. Whether the file is
+    /// signed is decided by its first line, so the input is parsed only once.
+    pub(crate) fn parse_fields<'a, R>(
+        text: &'a str,
+        options: &SecurityTxtOptions,
+        f: impl for<'b> FnOnce(Vec<RawField<'b>>, &'b str, &SourceMap<'a>, Option<SignatureInfo>) -> Result<R, ParseError>,
+    ) -> Result<R, ParseError> {
+        let unsigned_parser = SecurityTxtParser::new(options);
+        let signed_parser = PGPCleartextMessageParser::new(options);
+
+        if !signed_parser.is_signed(text) {
+            let source = SourceMap::new(text);
+            let fields = unsigned_parser.parse(text, &source)?;
+            return f(fields, text, &source, None);
         }
 
-        Ok(())
+        let msg = signed_parser.parse(text)?;
+        let source = SourceMap::from_slices(text, &msg.cleartext_lines);
+        let fields = unsigned_parser.parse(&msg.cleartext, &source)?;
+        let signature = SignatureInfo::new(&msg);
+        f(fields, &msg.cleartext, &source, Some(signature))
     }
 
-    pub(crate) fn new(fields: Vec<RawField>, options: &SecurityTxtOptions) -> Result<Self, ParseError> {
-        let mut acknowledgments: Vec<AcknowledgmentsField> = vec![];
-        let mut canonical: Vec<CanonicalField> = vec![];
-        let mut contact: Vec<ContactField> = vec![];
-        let mut csaf: Vec<CsafField> = vec![];
-        let mut encryption: Vec<EncryptionField> = vec![];
-        let mut expires: Vec<ExpiresField> = vec![];
-        let mut extension: Vec<ExtensionField> = vec![];
-        let mut hiring: Vec<HiringField> = vec![];
-        let mut policy: Vec<PolicyField> = vec![];
-        let mut preferred_languages: Vec<PreferredLanguagesField> = vec![];
-
-        for field in fields {
-            let name = field.name.to_lowercase();
-
-            match &name[..] {
-                "acknowledgments" => acknowledgments.push(AcknowledgmentsField::new(field.value)?),
-                "canonical" => canonical.push(CanonicalField::new(field.value)?),
-                "contact" => contact.push(ContactField::new(field.value)?),
-                "csaf" => csaf.push(CsafField::new(field.value)?),
-                "encryption" => encryption.push(EncryptionField::new(field.value)?),
-                "expires" => expires.push(ExpiresField::new(field.value, options.now)?),
-                "hiring" => hiring.push(HiringField::new(field.value)?),
-                "policy" => policy.push(PolicyField::new(field.value)?),
-                "preferred-languages" => preferred_languages.push(PreferredLanguagesField::new(field.value)?),
-                _ => extension.push(ExtensionField::new(name, field.value.to_owned())?),
-            }
-        }
+    /// Parses a security.txt file given as raw bytes, e.g., the body of an HTTP response.
+    pub fn parse_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        let options = Default::default();
+        Self::parse_bytes_with(bytes, &options)
+    }
 
-        Self::validate_contact_fields(&contact)?;
-        Self::validate_expires(&expires)?;
-        Self::validate_preferred_languages(&preferred_languages)?;
-
-        Ok(SecurityTxt {
-            acknowledgments,
-            canonical,
-            contact,
-            csaf,
-            encryption,
-            expires: expires.pop().unwrap(), // checked in Self::validate_expires()
-            extension,
-            hiring,
-            policy,
-            preferred_languages: preferred_languages.pop(),
-        })
+    /// Parses a security.txt file given as raw bytes, e.g., the body of an HTTP response.
+    ///
+    /// The input must not exceed the `max_size` option, and must be encoded as UTF-8. Invalid UTF-8 is reported as
+    /// [`ParseError::InvalidEncoding`] together with its location. Unless the `strict` option is set, a byte order mark
+    /// is removed, and input starting with a UTF-16 byte order mark is decoded as UTF-16. Spans refer to the decoded
+    /// text in that case.
+    pub fn parse_bytes_with(bytes: &[u8], options: &SecurityTxtOptions) -> Result<Self, ParseError> {
+        let text = input::decode(bytes, options)?;
+        Self::parse_with(&text, options)
     }
 
-    /// Parses a security.txt file as a string according to [RFC 9116](https://www.rfc-editor.org/rfc/rfc9116).
-    pub fn parse(text: &str) -> Result<Self, ParseError> {
+    /// Decodes a security.txt file given as raw bytes without parsing it.
+    ///
+    /// The input is checked and decoded in the same way as by [`SecurityTxt::parse_bytes_with`], except that a UTF-8
+    /// byte order mark is kept, so that it can be reported by [`SecurityTxt::diagnose`].
+    pub fn decode_bytes<'a>(bytes: &'a [u8], options: &SecurityTxtOptions) -> Result<Cow<'a, str>, ParseError> {
+        input::decode(bytes, options)
+    }
+
+    /// Reads the raw bytes of a security.txt file from the given reader without decoding them.
+    ///
+    /// Reading stops once the input exceeds the `max_size` option, which is then reported by
+    /// [`SecurityTxt::decode_bytes`].
+    pub fn read_bytes(reader: impl Read, options: &SecurityTxtOptions) -> Result<Vec<u8>, ParseError> {
+        input::read(reader, options)
+    }
+
+    /// Parses a security.txt file read from the given reader.
+    pub fn parse_reader(reader: impl Read) -> Result<Self, ParseError> {
         let options = Default::default();
-        Self::parse_with(text, &options)
+        Self::parse_reader_with(reader, &options)
     }
 
-    /// Parses a security.txt file as a string according to [RFC 9116](https://www.rfc-editor.org/rfc/rfc9116).
-    pub fn parse_with(text: &str, options: &SecurityTxtOptions) -> Result<Self, ParseError> {
+    /// Parses a security.txt file read from the given reader.
+    ///
+    /// Reading stops once the input exceeds the `max_size` option. See [`SecurityTxt::parse_bytes_with`] for how the
+    /// input is decoded.
+    pub fn parse_reader_with(reader: impl Read, options: &SecurityTxtOptions) -> Result<Self, ParseError> {
+        let bytes = input::read(reader, options)?;
+        Self::parse_bytes_with(&bytes, options)
+    }
+
+    /// Parses a security.txt file as a string in diagnostics mode.
+    ///
+    /// Instead of failing on the first problem, all problems found in the file are collected together with all fields
+    /// which could be parsed. See [`Diagnostics`] for details.
+    ///
+    /// Unless the `strict` option is set, every deviation from the grammar which is accepted by the lenient grammar is
+    /// reported as a warning. If the file starts like a signed message but its framework is broken, the fields cannot
+    /// be told apart from the framework, so a single [`ParseError::MalformedSignedMessage`] is reported instead.
+    pub fn diagnose(text: &str, options: &SecurityTxtOptions) -> Diagnostics {
+        let signed_parser = PGPCleartextMessageParser::new(options);
+
+        let mut diagnostics = match signed_parser.is_signed(text) {
+            true => match signed_parser.parse(text) {
+                Ok(msg) => {
+                    let source = SourceMap::from_slices(text, &msg.cleartext_lines);
+                    let signature = SignatureInfo::new(&msg);
+                    Self::diagnose_unsigned(&msg.cleartext, &source, Some(signature), options)
+                }
+                Err(err) => Diagnostics {
+                    findings: vec![Finding::error(err)],
+                    partial: Default::default(),
+                },
+            },
+            false => Self::diagnose_unsigned(text, &SourceMap::new(text), None, options),
+        };
+
+        if !options.strict && text.starts_with(BOM) {
+            let span = SourceMap::new(text).span(0..BOM.len_utf8());
+            diagnostics
+                .findings
+                .push(Finding::warning(ParseError::ByteOrderMark { span: Some(span) }));
+        }
+
+        // Findings which do not relate to a specific line are placed last.
+        diagnostics
+            .findings
+            .sort_by_key(|f| f.span().map_or(usize::MAX, |s| s.start));
+
+        diagnostics
+    }
+
+    fn diagnose_unsigned(
+        text: &str,
+        source: &SourceMap,
+        signature: Option<SignatureInfo>,
+        options: &SecurityTxtOptions,
+    ) -> Diagnostics {
         let unsigned_parser = SecurityTxtParser::new(options);
+        let (fields, malformed) = unsigned_parser.parse_recovering(text);
 
-        match unsigned_parser.parse(text) {
-            Ok(fields) => {
-                let fields: Vec<RawField> = fields.into_iter().flatten().collect();
-                Self::new(fields, options)
-            }
-            _ => {
-                let signed_parser = PGPCleartextMessageParser::new(options);
-                let msg = signed_parser.parse(text)?;
-                let fields = unsigned_parser.parse(&msg.cleartext)?;
-                let fields: Vec<RawField> = fields.into_iter().flatten().collect();
-                Self::new(fields, options)
-            }
+        let mut findings: Vec<Finding> = malformed
+            .iter()
+            .map(|line| {
+                let line = line.trim_end_matches(['\r', '\n']);
+                let error = ParseError::Malformed {
+                    value: line.to_owned(),
+                    span: Some(source.span_of(text, line)),
+                };
+                Finding::error(error)
+            })
+            .collect();
+        if text.is_empty() {
+            let error = ParseError::Malformed {
+                value: String::new(),
+                span: Some(source.span(0..0)),
+            };
+            findings.push(Finding::error(error));
         }
+        findings.extend(
+            unsigned_parser
+                .relaxations(text, &fields)
+                .into_iter()
+                .map(|(err, range)| Finding::warning(err.with_span(Some(source.span(range))))),
+        );
+
+        let locate = |field: &RawField| Some(source.span(field.range(text)));
+        let mut partial = PartialSecurityTxt::new(fields, locate, options, &mut findings);
+        partial.signature = signature;
+
+        Diagnostics { findings, partial }
+    }
+}
+
+impl SecurityTxt {
+    /// Returns whether one of the "Canonical" fields refers to the given URL.
+    ///
+    /// The URLs are compared in their normalized form, so differences in, e.g., the case of the host or an explicit
+    /// default port do not matter. Fragments are ignored. If there is no "Canonical" field, nothing matches.
+    pub fn matches_canonical(&self, url: &Url) -> bool {
+        let normalize = |url: &Url| {
+            let mut url = url.clone();
+            url.set_fragment(None);
+            url
+        };
+        let url = normalize(url);
+
+        self.canonical
+            .iter()
+            .filter_map(|field| Url::parse(field.uri.as_str()).ok())
+            .any(|canonical| normalize(&canonical) == url)
     }
 }
 
@@ -0,0 +1,165 @@
+use super::diagnostics::Diagnostics;
+use super::parse_error::ParseError;
+use super::parsers::{is_ftext_char, is_vchar, is_wsp};
+use super::raw_field::RawField;
+use super::securitytxt::SecurityTxt;
+use super::securitytxt_options::SecurityTxtOptions;
+use super::securitytxt_partial::PartialSecurityTxt;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+/// The names of the fields which have a dedicated method, and which are thus parsed as such
+const STANDARD_FIELDS: [&str; 9] = [
+    "Acknowledgments",
+    "Canonical",
+    "Contact",
+    "CSAF",
+    "Encryption",
+    "Expires",
+    "Hiring",
+    "Policy",
+    "Preferred-Languages",
+];
+
+/// A builder for constructing a [`SecurityTxt`] programmatically
+///
+/// The fields are validated according to the same rules as when parsing a security.txt file. In particular, at least
+/// one "Contact" field and exactly one "Expires" field are required, and links must use HTTPS.
+///
+/// ```
+/// use chrono::{Duration, Utc};
+/// use sectxtlib::SecurityTxtBuilder;
+///
+/// let txt = SecurityTxtBuilder::new()
+///     .contact("mailto:security@example.com")
+///     .expires(Utc::now() + Duration::days(180))
+///     .preferred_languages(&["en", "de"])
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(txt.contact.len(), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SecurityTxtBuilder {
+    fields: Vec<(String, String)>,
+    reserved: Option<String>,
+}
+
+impl SecurityTxtBuilder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn field(mut self, name: &str, value: &str) -> Self {
+        self.fields.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Adds an "Acknowledgments" field
+    pub fn acknowledgments(self, uri: &str) -> Self {
+        self.field("Acknowledgments", uri)
+    }
+
+    /// Adds a "Canonical" field
+    pub fn canonical(self, uri: &str) -> Self {
+        self.field("Canonical", uri)
+    }
+
+    /// Adds a "Contact" field, with earlier contacts taking priority over later ones
+    pub fn contact(self, uri: &str) -> Self {
+        self.field("Contact", uri)
+    }
+
+    /// Adds a "CSAF" field
+    pub fn csaf(self, uri: &str) -> Self {
+        self.field("CSAF", uri)
+    }
+
+    /// Adds an "Encryption" field
+    pub fn encryption(self, uri: &str) -> Self {
+        self.field("Encryption", uri)
+    }
+
+    /// Adds the "Expires" field, which must be specified exactly once
+    pub fn expires(self, datetime: DateTime<Utc>) -> Self {
+        self.field("Expires", &datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+
+    /// Adds a "Hiring" field
+    pub fn hiring(self, uri: &str) -> Self {
+        self.field("Hiring", uri)
+    }
+
+    /// Adds a "Policy" field
+    pub fn policy(self, uri: &str) -> Self {
+        self.field("Policy", uri)
+    }
+
+    /// Adds the "Preferred-Languages" field, which may be specified at most once
+    pub fn preferred_languages(self, languages: &[&str]) -> Self {
+        self.field("Preferred-Languages", &languages.join(", "))
+    }
+
+    /// Adds a field not explicitly supported by this library
+    ///
+    /// The name must not be the one of a standard field, ignoring its case, as the field would be parsed as such.
+    /// Building fails with [`ParseError::ReservedExtensionField`] otherwise.
+    pub fn extension(mut self, name: &str, value: &str) -> Self {
+        if STANDARD_FIELDS.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            self.reserved.get_or_insert_with(|| name.to_owned());
+            return self;
+        }
+
+        self.field(name, value)
+    }
+
+    fn validate_field(name: &str, value: &str) -> Result<(), ParseError> {
+        let illegal = |cause: &str| ParseError::IllegalField {
+            field: name.to_owned(),
+            value: value.to_owned(),
+            cause: cause.to_owned(),
+            span: None,
+        };
+
+        if name.is_empty() || !name.chars().all(is_ftext_char) {
+            return Err(illegal(
+                "field name must consist of printable characters other than a colon",
+            ));
+        }
+        if !value.chars().all(|x| is_vchar(x) || is_wsp(x)) {
+            return Err(illegal(
+                "field value must consist of printable characters and whitespace",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Builds the [`SecurityTxt`], validating the "Expires" field against the current time
+    pub fn build(&self) -> Result<SecurityTxt, ParseError> {
+        self.build_with(&Default::default())
+    }
+
+    /// Builds the [`SecurityTxt`] with the given options
+    pub fn build_with(&self, options: &SecurityTxtOptions) -> Result<SecurityTxt, ParseError> {
+        if let Some(field) = &self.reserved {
+            return Err(ParseError::ReservedExtensionField {
+                field: field.to_owned(),
+                span: None,
+            });
+        }
+        for (name, value) in &self.fields {
+            Self::validate_field(name, value)?;
+        }
+
+        let fields = self
+            .fields
+            .iter()
+            .map(|(name, value)| RawField { name, value })
+            .collect();
+
+        let mut findings = vec![];
+        let partial = PartialSecurityTxt::new(fields, |_| None, options, &mut findings);
+
+        Diagnostics { findings, partial }.into_result()
+    }
+}
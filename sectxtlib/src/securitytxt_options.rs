@@ -1,4 +1,18 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+
+/// How to handle an "Expires" field which specifies a time in the past
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExpiryPolicy {
+    /// Reject the file as invalid, as required by [RFC 9116](https://www.rfc-editor.org/rfc/rfc9116#name-expires)
+    #[default]
+    Reject,
+
+    /// Accept the file, but report a warning
+    Warn,
+
+    /// Accept the file without reporting anything
+    Ignore,
+}
 
 /// Options for parsing a security.txt file
 #[derive(Clone, Debug)]
@@ -6,24 +20,44 @@ pub struct SecurityTxtOptions {
     /// The current date and time to validate the "Expires" field against
     pub now: DateTime<Utc>,
 
-    /// Whether to be strict with line endings or more relaxed
+    /// Whether to use the strict grammar of RFC 9116, or a lenient grammar which accepts common deviations
+    ///
+    /// The lenient grammar accepts a bare CR as line ending, a missing line ending at the end of the file, a byte order
+    /// mark at the start of the file, and a tab after the field separator. See [`SecurityTxt::diagnose`](crate::SecurityTxt::diagnose)
+    /// for reporting these deviations as warnings.
     pub strict: bool,
+
+    /// How to handle an "Expires" field which specifies a time in the past
+    pub expiry: ExpiryPolicy,
+
+    /// If set, a warning is reported when the "Expires" field specifies a time within this duration from now
+    pub expires_soon: Option<Duration>,
+
+    /// The maximum size of the input in bytes, or `None` for no limit
+    ///
+    /// The limit applies to input which is not yet decoded, i.e., [`SecurityTxt::parse_bytes_with`](crate::SecurityTxt::parse_bytes_with)
+    /// and [`SecurityTxt::parse_reader_with`](crate::SecurityTxt::parse_reader_with). The reader is not consumed
+    /// beyond the limit.
+    pub max_size: Option<usize>,
 }
 
+/// The default maximum size of the input, which is far larger than any reasonable security.txt file
+const DEFAULT_MAX_SIZE: usize = 1024 * 1024;
+
 impl SecurityTxtOptions {
     pub fn new(strict: bool) -> Self {
         Self {
             now: Utc::now(),
             strict,
+            expiry: ExpiryPolicy::Reject,
+            expires_soon: None,
+            max_size: Some(DEFAULT_MAX_SIZE),
         }
     }
 }
 
 impl Default for SecurityTxtOptions {
     fn default() -> Self {
-        Self {
-            now: Utc::now(),
-            strict: true,
-        }
+        Self::new(true)
     }
 }
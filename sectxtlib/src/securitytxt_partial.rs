@@ -0,0 +1,301 @@
+use super::diagnostics::Finding;
+use super::fields::{
+    parse_datetime, parse_languages, parse_uri, AcknowledgmentsField, CanonicalField, ContactField, CsafField,
+    EncryptionField, ExpiresField, ExtensionField, HiringField, PolicyField, PreferredLanguagesField,
+};
+use super::parse_error::ParseError;
+use super::raw_field::RawField;
+use super::securitytxt::SecurityTxt;
+use super::securitytxt_options::{ExpiryPolicy, SecurityTxtOptions};
+use super::securitytxt_ref::{ExpiresFieldRef, ExtensionFieldRef, PreferredLanguagesFieldRef, UriFieldRef};
+use super::signature_info::SignatureInfo;
+use super::span::Span;
+use chrono::{DateTime, Utc};
+use iri_string::types::IriStr;
+use oxilangtag::LanguageTag;
+use std::borrow::Cow;
+
+/// A best-effort representation of a security.txt file which may violate [RFC 9116](https://www.rfc-editor.org/rfc/rfc9116)
+///
+/// Fields which could not be parsed are left out. If a field may only be specified once but appears multiple times,
+/// the first occurrence is kept.
+#[derive(Debug, Default, PartialEq)]
+pub struct PartialSecurityTxt {
+    /// A collection of "Acknowledgments" fields
+    pub acknowledgments: Vec<AcknowledgmentsField>,
+
+    /// A collection of "Canonical" fields
+    pub canonical: Vec<CanonicalField>,
+
+    /// A collection of "Contact" fields
+    pub contact: Vec<ContactField>,
+
+    /// A collection of "CSAF" fields,
+    pub csaf: Vec<CsafField>,
+
+    /// A collection of "Encryption" fields
+    pub encryption: Vec<EncryptionField>,
+
+    /// The "Expires" field, if available
+    pub expires: Option<ExpiresField>,
+
+    /// A collection of "Extension" fields
+    pub extension: Vec<ExtensionField>,
+
+    /// A collection of "Hiring" fields
+    pub hiring: Vec<HiringField>,
+
+    /// A collection of "Policy" fields
+    pub policy: Vec<PolicyField>,
+
+    /// The "Preferred-Languages" field, if available
+    pub preferred_languages: Option<PreferredLanguagesField>,
+
+    /// Metadata about the OpenPGP signature, if the file was signed
+    pub signature: Option<SignatureInfo>,
+}
+
+impl PartialSecurityTxt {
+    pub(crate) fn validate_contact_fields<T>(fields: &[T]) -> Result<(), ParseError> {
+        if fields.is_empty() {
+            return Err(ParseError::ContactFieldMissing);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn validate_expires<T>(fields: &[T]) -> Result<(), ParseError> {
+        if fields.is_empty() {
+            return Err(ParseError::ExpiresFieldMissing);
+        }
+        if fields.len() > 1 {
+            return Err(ParseError::ExpiresFieldMultiple { span: None });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn validate_expiry(
+        datetime: DateTime<Utc>,
+        options: &SecurityTxtOptions,
+        span: Option<Span>,
+    ) -> Option<Finding> {
+        let value = datetime.to_rfc3339();
+
+        if datetime < options.now {
+            let error = ParseError::ExpiresFieldExpired { value, span };
+            return match options.expiry {
+                ExpiryPolicy::Reject => Some(Finding::error(error)),
+                ExpiryPolicy::Warn => Some(Finding::warning(error)),
+                ExpiryPolicy::Ignore => None,
+            };
+        }
+
+        match options.expires_soon {
+            Some(window) if datetime < options.now + window => {
+                let error = ParseError::ExpiresFieldExpiresSoon { value, span };
+                Some(Finding::warning(error))
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn validate_preferred_languages<T>(fields: &[T]) -> Result<(), ParseError> {
+        if fields.len() > 1 {
+            return Err(ParseError::PreferredLanguagesFieldMultiple { span: None });
+        }
+
+        Ok(())
+    }
+
+    /// Collects all fields which can be parsed, and records a [`Finding`] for every problem encountered.
+    ///
+    /// The location of each field is determined by `locate`.
+    pub(crate) fn new(
+        fields: Vec<RawField>,
+        locate: impl Fn(&RawField) -> Option<Span>,
+        options: &SecurityTxtOptions,
+        findings: &mut Vec<Finding>,
+    ) -> Self {
+        let fields = fields
+            .iter()
+            .map(|field| (Cow::Borrowed(field.name), Cow::Borrowed(field.value), locate(field)));
+
+        PartialSecurityTxtRef::new(fields, options, findings).into()
+    }
+}
+
+/// The fields of a file which could be parsed, borrowing from the parsed input
+///
+/// Both [`PartialSecurityTxt`] and [`SecurityTxtRef`](crate::SecurityTxtRef) are built from it, so that owned and
+/// borrowed files are validated by the same routine.
+#[derive(Default)]
+pub(crate) struct PartialSecurityTxtRef<'a> {
+    pub acknowledgments: Vec<UriFieldRef<'a>>,
+    pub canonical: Vec<UriFieldRef<'a>>,
+    pub contact: Vec<UriFieldRef<'a>>,
+    pub csaf: Vec<UriFieldRef<'a>>,
+    pub encryption: Vec<UriFieldRef<'a>>,
+    pub expires: Option<ExpiresFieldRef>,
+    pub extension: Vec<ExtensionFieldRef<'a>>,
+    pub hiring: Vec<UriFieldRef<'a>>,
+    pub policy: Vec<UriFieldRef<'a>>,
+    pub preferred_languages: Option<PreferredLanguagesFieldRef<'a>>,
+    pub signature: Option<SignatureInfo>,
+}
+
+impl<'a> PartialSecurityTxtRef<'a> {
+    /// Collects all fields which can be parsed, and records a [`Finding`] for every problem encountered.
+    ///
+    /// Each field is given by its name, its value, and its location.
+    pub(crate) fn new(
+        fields: impl IntoIterator<Item = (Cow<'a, str>, Cow<'a, str>, Option<Span>)>,
+        options: &SecurityTxtOptions,
+        findings: &mut Vec<Finding>,
+    ) -> Self {
+        let mut acknowledgments = vec![];
+        let mut canonical = vec![];
+        let mut contact = vec![];
+        let mut csaf = vec![];
+        let mut encryption = vec![];
+        let mut expires = vec![];
+        let mut extension = vec![];
+        let mut hiring = vec![];
+        let mut policy = vec![];
+        let mut preferred_languages = vec![];
+
+        for (name, value, span) in fields {
+            // Field names consist of ASCII characters only, so they can be compared without allocating.
+            let uri_fields = [
+                ("Acknowledgments", &mut acknowledgments),
+                ("Canonical", &mut canonical),
+                ("Contact", &mut contact),
+                ("CSAF", &mut csaf),
+                ("Encryption", &mut encryption),
+                ("Hiring", &mut hiring),
+                ("Policy", &mut policy),
+            ];
+
+            let result =
+                if let Some((known, fields)) = uri_fields.into_iter().find(|(n, _)| n.eq_ignore_ascii_case(&name)) {
+                    parse_uri_ref(known, &value).map(|uri| {
+                        fields.push(UriFieldRef {
+                            uri,
+                            span: span.clone(),
+                        })
+                    })
+                } else if name.eq_ignore_ascii_case("Expires") {
+                    parse_datetime(&value).map(|datetime| {
+                        findings.extend(PartialSecurityTxt::validate_expiry(datetime, options, span.clone()));
+                        expires.push(ExpiresFieldRef::new(datetime, span.clone()))
+                    })
+                } else if name.eq_ignore_ascii_case("Preferred-Languages") {
+                    parse_languages_ref(&value).map(|languages| {
+                        preferred_languages.push(PreferredLanguagesFieldRef {
+                            languages,
+                            span: span.clone(),
+                        })
+                    })
+                } else {
+                    extension.push(ExtensionFieldRef {
+                        name,
+                        value,
+                        span: span.clone(),
+                    });
+                    Ok(())
+                };
+
+            if let Err(err) = result {
+                findings.push(Finding::error(err.with_span(span.clone())));
+            }
+        }
+
+        // Duplicate fields are reported at their second occurrence.
+        let validations = [
+            (PartialSecurityTxt::validate_contact_fields(&contact), None),
+            (
+                PartialSecurityTxt::validate_expires(&expires),
+                expires.get(1).and_then(|f| f.span.clone()),
+            ),
+            (
+                PartialSecurityTxt::validate_preferred_languages(&preferred_languages),
+                preferred_languages.get(1).and_then(|f| f.span.clone()),
+            ),
+        ];
+        for (result, span) in validations {
+            if let Err(err) = result {
+                findings.push(Finding::error(err.with_span(span)));
+            }
+        }
+
+        Self {
+            acknowledgments,
+            canonical,
+            contact,
+            csaf,
+            encryption,
+            expires: expires.into_iter().next(),
+            extension,
+            hiring,
+            policy,
+            preferred_languages: preferred_languages.into_iter().next(),
+            signature: None,
+        }
+    }
+}
+
+/// Parses a URI which borrows from the value, unless the value itself is a copy
+fn parse_uri_ref<'a>(field: &str, value: &Cow<'a, str>) -> Result<Cow<'a, IriStr>, ParseError> {
+    match value {
+        Cow::Borrowed(value) => parse_uri(field, value).map(Cow::Borrowed),
+        Cow::Owned(value) => parse_uri(field, value).map(|uri| Cow::Owned(uri.to_owned())),
+    }
+}
+
+/// Parses language tags which borrow from the value, unless the value itself is a copy
+fn parse_languages_ref<'a>(value: &Cow<'a, str>) -> Result<Vec<LanguageTag<Cow<'a, str>>>, ParseError> {
+    match value {
+        Cow::Borrowed(value) => parse_languages(value, |tag| LanguageTag::parse(Cow::Borrowed(tag))),
+        Cow::Owned(value) => parse_languages(value, |tag| LanguageTag::parse(Cow::Owned(tag.to_owned()))),
+    }
+}
+
+impl From<PartialSecurityTxtRef<'_>> for PartialSecurityTxt {
+    fn from(partial: PartialSecurityTxtRef<'_>) -> Self {
+        Self {
+            acknowledgments: partial.acknowledgments.iter().map(Into::into).collect(),
+            canonical: partial.canonical.iter().map(Into::into).collect(),
+            contact: partial.contact.iter().map(Into::into).collect(),
+            csaf: partial.csaf.iter().map(Into::into).collect(),
+            encryption: partial.encryption.iter().map(Into::into).collect(),
+            expires: partial.expires.as_ref().map(Into::into),
+            extension: partial.extension.iter().map(Into::into).collect(),
+            hiring: partial.hiring.iter().map(Into::into).collect(),
+            policy: partial.policy.iter().map(Into::into).collect(),
+            preferred_languages: partial.preferred_languages.as_ref().map(Into::into),
+            signature: partial.signature,
+        }
+    }
+}
+
+impl TryFrom<PartialSecurityTxt> for SecurityTxt {
+    type Error = ParseError;
+
+    fn try_from(partial: PartialSecurityTxt) -> Result<Self, Self::Error> {
+        PartialSecurityTxt::validate_contact_fields(&partial.contact)?;
+
+        Ok(SecurityTxt {
+            acknowledgments: partial.acknowledgments,
+            canonical: partial.canonical,
+            contact: partial.contact,
+            csaf: partial.csaf,
+            encryption: partial.encryption,
+            expires: partial.expires.ok_or(ParseError::ExpiresFieldMissing)?,
+            extension: partial.extension,
+            hiring: partial.hiring,
+            policy: partial.policy,
+            preferred_languages: partial.preferred_languages,
+            signature: partial.signature,
+        })
+    }
+}
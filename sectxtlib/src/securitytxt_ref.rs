@@ -0,0 +1,263 @@
+use super::diagnostics::first_error;
+use super::parse_error::ParseError;
+use super::securitytxt::SecurityTxt;
+use super::securitytxt_options::SecurityTxtOptions;
+use super::securitytxt_partial::{PartialSecurityTxt, PartialSecurityTxtRef};
+use super::signature_info::SignatureInfo;
+use super::span::Span;
+use chrono::{DateTime, Utc};
+use iri_string::types::IriStr;
+use oxilangtag::LanguageTag;
+use std::borrow::Cow;
+use std::sync::OnceLock;
+use valuable::{Fields, Listable, NamedField, NamedValues, StructDef, Structable, Valuable, Value, Visit};
+
+/// A field with a link, borrowing the URI from the parsed input
+#[derive(Clone, Debug, PartialEq)]
+pub struct UriFieldRef<'a> {
+    /// The URI of the link according to [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986)
+    pub uri: Cow<'a, IriStr>,
+
+    /// The location of the field in the parsed file
+    pub span: Option<Span>,
+}
+
+impl Valuable for UriFieldRef<'_> {
+    fn as_value(&self) -> Value<'_> {
+        Value::String(self.uri.as_str())
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        visit.visit_value(self.as_value());
+    }
+}
+
+/// The "Expires" field of a [`SecurityTxtRef`]
+#[derive(Clone, Debug)]
+pub struct ExpiresFieldRef {
+    /// The date and time from which the security.txt file is considered stale
+    pub datetime: DateTime<Utc>,
+
+    /// The location of the field in the parsed file
+    pub span: Option<Span>,
+
+    // Formatted only when the field is logged
+    log_value: OnceLock<String>,
+}
+
+impl ExpiresFieldRef {
+    pub(crate) fn new(datetime: DateTime<Utc>, span: Option<Span>) -> Self {
+        Self {
+            datetime,
+            span,
+            log_value: OnceLock::new(),
+        }
+    }
+
+    /// Returns whether the field specifies a time before `now`
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.datetime < now
+    }
+}
+
+impl PartialEq for ExpiresFieldRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.datetime == other.datetime && self.span == other.span
+    }
+}
+
+impl Valuable for ExpiresFieldRef {
+    fn as_value(&self) -> Value<'_> {
+        Value::String(self.log_value.get_or_init(|| self.datetime.to_rfc3339()))
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        visit.visit_value(self.as_value());
+    }
+}
+
+/// The "Preferred-Languages" field, borrowing the language tags from the parsed input
+///
+/// Unlike [`PreferredLanguagesField`](crate::PreferredLanguagesField), the language tags are not normalized.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreferredLanguagesFieldRef<'a> {
+    /// The set of preferred languages according to [RFC 5646](https://www.rfc-editor.org/rfc/rfc5646)
+    pub languages: Vec<LanguageTag<Cow<'a, str>>>,
+
+    /// The location of the field in the parsed file
+    pub span: Option<Span>,
+}
+
+impl Valuable for PreferredLanguagesFieldRef<'_> {
+    fn as_value(&self) -> Value<'_> {
+        Value::Listable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        for tag in &self.languages {
+            visit.visit_value(Value::String(tag.as_str()));
+        }
+    }
+}
+
+impl Listable for PreferredLanguagesFieldRef<'_> {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.languages.len(), Some(self.languages.len()))
+    }
+}
+
+/// A field not explicitly supported by this library, borrowing its name and value from the parsed input
+///
+/// Unlike [`ExtensionField`](crate::ExtensionField), the name keeps its original case. A value which is folded
+/// across lines of a signed file does not appear verbatim in the input if one of the lines is dash-escaped, so it is
+/// copied in that case.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtensionFieldRef<'a> {
+    /// Name of the extension field
+    pub name: Cow<'a, str>,
+    /// Value of the extension field
+    pub value: Cow<'a, str>,
+    /// The location of the field in the parsed file
+    pub span: Option<Span>,
+}
+
+static EXTENSION_FIELD_REF_FIELDS: &[NamedField<'static>] = &[
+    NamedField::new("name"),
+    NamedField::new("value"),
+    NamedField::new("span"),
+];
+
+impl Valuable for ExtensionFieldRef<'_> {
+    fn as_value(&self) -> Value<'_> {
+        Value::Structable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        let values = [
+            Value::String(&self.name),
+            Value::String(&self.value),
+            self.span.as_value(),
+        ];
+        visit.visit_named_fields(&NamedValues::new(EXTENSION_FIELD_REF_FIELDS, &values));
+    }
+}
+
+impl Structable for ExtensionFieldRef<'_> {
+    fn definition(&self) -> StructDef<'_> {
+        StructDef::new_static("ExtensionFieldRef", Fields::Named(EXTENSION_FIELD_REF_FIELDS))
+    }
+}
+
+/// A security.txt file which borrows from the parsed input instead of allocating every field
+///
+/// The file is validated in the same way as by [`SecurityTxt::parse_with`]. Use [`SecurityTxtRef::into_owned`] to
+/// convert it into a [`SecurityTxt`] on demand. For a signed file, fields still borrow from the original input, not
+/// from the cleartext.
+#[derive(Clone, Debug, PartialEq, Valuable)]
+pub struct SecurityTxtRef<'a> {
+    /// A collection of "Acknowledgments" fields
+    pub acknowledgments: Vec<UriFieldRef<'a>>,
+
+    /// A collection of "Canonical" fields
+    pub canonical: Vec<UriFieldRef<'a>>,
+
+    /// A collection of "Contact" fields
+    pub contact: Vec<UriFieldRef<'a>>,
+
+    /// A collection of "CSAF" fields,
+    pub csaf: Vec<UriFieldRef<'a>>,
+
+    /// A collection of "Encryption" fields
+    pub encryption: Vec<UriFieldRef<'a>>,
+
+    /// The "Expires" field
+    pub expires: ExpiresFieldRef,
+
+    /// A collection of "Extension" fields
+    pub extension: Vec<ExtensionFieldRef<'a>>,
+
+    /// A collection of "Hiring" fields
+    pub hiring: Vec<UriFieldRef<'a>>,
+
+    /// A collection of "Policy" fields
+    pub policy: Vec<UriFieldRef<'a>>,
+
+    /// The "Preferred-Languages" field, if available
+    pub preferred_languages: Option<PreferredLanguagesFieldRef<'a>>,
+
+    /// Metadata about the OpenPGP signature, if the file was signed
+    pub signature: Option<SignatureInfo>,
+}
+
+impl<'a> SecurityTxtRef<'a> {
+    /// Parses a security.txt file as a string according to [RFC 9116](https://www.rfc-editor.org/rfc/rfc9116).
+    pub fn parse(text: &'a str) -> Result<Self, ParseError> {
+        let options = Default::default();
+        Self::parse_with(text, &options)
+    }
+
+    /// Parses a security.txt file as a string according to [RFC 9116](https://www.rfc-editor.org/rfc/rfc9116).
+    ///
+    /// Fails with the same error as [`SecurityTxt::parse_with`] if the file is not valid.
+    pub fn parse_with(text: &'a str, options: &SecurityTxtOptions) -> Result<Self, ParseError> {
+        SecurityTxt::parse_fields(text, options, |fields, cleartext, source, signature| {
+            let fields = fields.iter().map(|field| {
+                let name = source.original_slice(cleartext, field.name);
+                let value = source.original_slice(cleartext, field.value);
+                (name, value, Some(source.span(field.range(cleartext))))
+            });
+
+            let mut findings = vec![];
+            let mut partial = PartialSecurityTxtRef::new(fields, options, &mut findings);
+            partial.signature = signature;
+
+            first_error(findings)?;
+            partial.try_into()
+        })
+    }
+
+    /// Converts the file into a [`SecurityTxt`], which owns all of its fields
+    pub fn into_owned(self) -> SecurityTxt {
+        SecurityTxt {
+            acknowledgments: self.acknowledgments.iter().map(Into::into).collect(),
+            canonical: self.canonical.iter().map(Into::into).collect(),
+            contact: self.contact.iter().map(Into::into).collect(),
+            csaf: self.csaf.iter().map(Into::into).collect(),
+            encryption: self.encryption.iter().map(Into::into).collect(),
+            expires: (&self.expires).into(),
+            extension: self.extension.iter().map(Into::into).collect(),
+            hiring: self.hiring.iter().map(Into::into).collect(),
+            policy: self.policy.iter().map(Into::into).collect(),
+            preferred_languages: self.preferred_languages.as_ref().map(Into::into),
+            signature: self.signature,
+        }
+    }
+}
+
+impl From<SecurityTxtRef<'_>> for SecurityTxt {
+    fn from(txt: SecurityTxtRef<'_>) -> Self {
+        txt.into_owned()
+    }
+}
+
+impl<'a> TryFrom<PartialSecurityTxtRef<'a>> for SecurityTxtRef<'a> {
+    type Error = ParseError;
+
+    fn try_from(partial: PartialSecurityTxtRef<'a>) -> Result<Self, Self::Error> {
+        PartialSecurityTxt::validate_contact_fields(&partial.contact)?;
+
+        Ok(SecurityTxtRef {
+            acknowledgments: partial.acknowledgments,
+            canonical: partial.canonical,
+            contact: partial.contact,
+            csaf: partial.csaf,
+            encryption: partial.encryption,
+            expires: partial.expires.ok_or(ParseError::ExpiresFieldMissing)?,
+            extension: partial.extension,
+            hiring: partial.hiring,
+            policy: partial.policy,
+            preferred_languages: partial.preferred_languages,
+            signature: partial.signature,
+        })
+    }
+}
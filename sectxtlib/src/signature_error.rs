@@ -0,0 +1,28 @@
+use super::pgppacket::HashAlgorithm;
+use thiserror::Error;
+
+#[derive(Error, Clone, Debug, PartialEq)]
+pub enum SignatureError {
+    #[error("file is not signed")]
+    NotSigned,
+    #[error("invalid ASCII armor")]
+    InvalidArmor,
+    #[error("ASCII armor checksum does not match")]
+    ChecksumMismatch,
+    #[error("invalid OpenPGP packet")]
+    InvalidPacket,
+    #[error("unsupported OpenPGP packet version")]
+    UnsupportedVersion,
+    #[error("unsupported OpenPGP algorithm")]
+    UnsupportedAlgorithm,
+    #[error("signature is not a cleartext signature")]
+    UnsupportedSignatureType,
+    #[error("signature uses hash algorithm {0}, which is not declared in the \"Hash\" armor header")]
+    HashHeaderMismatch(HashAlgorithm),
+    #[error("signature uses insecure hash algorithm {0}")]
+    InsecureHashAlgorithm(HashAlgorithm),
+    #[error("secret key is protected by a passphrase")]
+    EncryptedKey,
+    #[error("no supported secret key found")]
+    SecretKeyMissing,
+}
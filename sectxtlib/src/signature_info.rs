@@ -0,0 +1,115 @@
+use super::pgparmor;
+use super::pgpcleartextmessage::PGPCleartextMessage;
+use super::pgppacket::{parse_packets, to_hex, HashAlgorithm, PublicKeyAlgorithm, SignaturePacket, TAG_SIGNATURE};
+use super::signature_error::SignatureError;
+use valuable::{Valuable, Value, Visit};
+
+/// Metadata about the [OpenPGP cleartext signature](https://www.rfc-editor.org/rfc/rfc4880#section-7) of a signed
+/// security.txt file
+///
+/// The signature is only decoded, not verified. Use `Keyring::verify` with the `openpgp` feature to check it against
+/// a set of public keys.
+///
+/// With the `serde` feature, all fields except `data` are serialized. When deserializing, the remaining fields are
+/// derived from `hash_algorithms`, `armor_headers`, and `armored`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "SignatureInfoRepr"))]
+pub struct SignatureInfo {
+    /// The hash algorithms declared in the "Hash" armor headers of the signed message
+    pub hash_algorithms: Vec<String>,
+
+    /// The armor headers of the signature block, e.g., "Version" or "Comment"
+    pub armor_headers: Vec<(String, String)>,
+
+    /// The base64 data of the signature block as it appears in the file, including the checksum line
+    pub armored: String,
+
+    /// The decoded signature packets, or the reason why they could not be decoded
+    ///
+    /// The CRC-24 checksum of the ASCII armor is verified if present.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub data: Result<Vec<u8>, SignatureError>,
+
+    /// The hash algorithm used for the signature, if the signature packet could be parsed
+    pub hash_algorithm: Option<HashAlgorithm>,
+
+    /// The public-key algorithm used for the signature, if the signature packet could be parsed
+    pub public_key_algorithm: Option<PublicKeyAlgorithm>,
+
+    /// The key ID of the issuing key as uppercase hex, if specified by the signature
+    pub issuer_key_id: Option<String>,
+
+    /// The fingerprint of the issuing key as uppercase hex, if specified by the signature
+    pub issuer_fingerprint: Option<String>,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    log_value: String,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SignatureInfoRepr {
+    hash_algorithms: Vec<String>,
+    armor_headers: Vec<(String, String)>,
+    armored: String,
+}
+
+#[cfg(feature = "serde")]
+impl From<SignatureInfoRepr> for SignatureInfo {
+    fn from(repr: SignatureInfoRepr) -> Self {
+        Self::from_parts(repr.hash_algorithms, repr.armor_headers, repr.armored)
+    }
+}
+
+impl SignatureInfo {
+    pub(crate) fn new(msg: &PGPCleartextMessage) -> Self {
+        Self::from_parts(
+            msg.hash_armor_headers.concat().into_iter().map(str::to_owned).collect(),
+            msg.signature
+                .keys
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            msg.signature.signature.to_owned(),
+        )
+    }
+
+    fn from_parts(hash_algorithms: Vec<String>, armor_headers: Vec<(String, String)>, armored: String) -> Self {
+        let data = pgparmor::decode(&armored);
+
+        let packets = data.as_deref().ok().and_then(|data| parse_packets(data).ok());
+        let signature = packets
+            .iter()
+            .flatten()
+            .find(|p| p.tag == TAG_SIGNATURE)
+            .and_then(|p| SignaturePacket::parse(p.body).ok());
+
+        let issuer_key_id = signature.as_ref().and_then(|s| s.issuer_key_id()).map(to_hex);
+        let issuer_fingerprint = signature.as_ref().and_then(|s| s.issuer_fingerprint()).map(to_hex);
+        let log_value = issuer_fingerprint
+            .clone()
+            .or_else(|| issuer_key_id.clone())
+            .unwrap_or_default();
+
+        Self {
+            hash_algorithms,
+            armor_headers,
+            armored,
+            hash_algorithm: signature.as_ref().map(|s| s.hash_algorithm),
+            public_key_algorithm: signature.as_ref().map(|s| s.public_key_algorithm),
+            issuer_key_id,
+            issuer_fingerprint,
+            data,
+            log_value,
+        }
+    }
+}
+
+impl Valuable for SignatureInfo {
+    fn as_value(&self) -> Value<'_> {
+        self.log_value.as_value()
+    }
+
+    fn visit(&self, _visit: &mut dyn Visit) {}
+}
@@ -0,0 +1,127 @@
+use nom::Offset;
+use std::borrow::Cow;
+use std::ops::Range;
+use valuable::Valuable;
+
+/// The location of an element in the original input
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Valuable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+    /// The line on which the element starts, starting at 1
+    pub line: usize,
+
+    /// The column at which the element starts, counted in characters and starting at 1
+    pub column: usize,
+
+    /// The byte offset at which the element starts
+    pub start: usize,
+
+    /// The byte offset at which the element ends, exclusively
+    pub end: usize,
+}
+
+impl Span {
+    /// Returns the byte range of the element in the original input
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+/// Maps byte ranges in a parsed text back to the original input
+///
+/// The parsed text is either the original input itself, or the cleartext of a PGP cleartext message. In the latter
+/// case, the cleartext consists of segments which were copied verbatim from the original input.
+pub(crate) struct SourceMap<'a> {
+    original: &'a str,
+    line_starts: Vec<usize>,
+    segments: Vec<(usize, usize)>,
+}
+
+impl<'a> SourceMap<'a> {
+    /// Creates a mapping for a text which is the original input itself
+    pub fn new(original: &'a str) -> Self {
+        Self::with_segments(original, vec![(0, 0)])
+    }
+
+    /// Creates a mapping for a text which consists of the given slices of the original input
+    pub fn from_slices(original: &'a str, slices: &[&str]) -> Self {
+        let mut local = 0;
+        let mut segments = Vec::with_capacity(slices.len());
+
+        for slice in slices {
+            segments.push((local, original.offset(slice)));
+            local += slice.len();
+        }
+
+        Self::with_segments(original, segments)
+    }
+
+    fn with_segments(original: &'a str, segments: Vec<(usize, usize)>) -> Self {
+        // A bare CR also ends a line, as accepted by the lenient grammar.
+        let line_starts = std::iter::once(0)
+            .chain(
+                original
+                    .match_indices(['\n', '\r'])
+                    .filter(|&(i, m)| m == "\n" || !original[i + 1..].starts_with('\n'))
+                    .map(|(i, _)| i + 1),
+            )
+            .collect();
+
+        Self {
+            original,
+            line_starts,
+            segments,
+        }
+    }
+
+    fn to_original(&self, local: usize) -> usize {
+        let idx = self.segments.partition_point(|&(start, _)| start <= local);
+
+        match idx {
+            0 => local,
+            _ => {
+                let (local_start, original_start) = self.segments[idx - 1];
+                original_start + (local - local_start)
+            }
+        }
+    }
+
+    /// Returns the span of the given byte range in the parsed text
+    pub fn span(&self, range: Range<usize>) -> Span {
+        let start = self.to_original(range.start);
+        let end = match range.is_empty() {
+            true => start,
+            false => self.to_original(range.end - 1) + 1,
+        };
+
+        let line = self.line_starts.partition_point(|&s| s <= start);
+        let line_start = self.line_starts[line - 1];
+        let column = self.original[line_start..start].chars().count() + 1;
+
+        Span {
+            line,
+            column,
+            start,
+            end,
+        }
+    }
+
+    /// Returns the span of a slice of the parsed text
+    pub fn span_of(&self, text: &str, slice: &str) -> Span {
+        let start = text.offset(slice);
+        self.span(start..start + slice.len())
+    }
+
+    /// Returns the slice of the original input which a slice of the parsed text was copied from
+    ///
+    /// A slice which spans multiple segments, e.g., a value folded across a dash-escaped line, does not appear verbatim
+    /// in the original input, so it is copied instead.
+    pub fn original_slice(&self, text: &str, slice: &str) -> Cow<'a, str> {
+        let start = self.to_original(text.offset(slice));
+
+        match self.original.get(start..start + slice.len()) {
+            Some(original) if original == slice => Cow::Borrowed(original),
+            _ => Cow::Owned(slice.to_owned()),
+        }
+    }
+}